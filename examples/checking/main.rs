@@ -36,8 +36,9 @@ fn main() {
         let values: Vec<f64> = selections
             .iter()
             .map(|&selection| {
-                let hetero = Heterozygosity::new(N_REDNECK, U, Selection::Fixed(selection), h)
-                    .expect("Could not construct Heterozygosity");
+                let hetero =
+                    Heterozygosity::new(N_REDNECK, U, Selection::Fixed(selection), h.clone())
+                        .expect("Could not construct Heterozygosity");
                 hetero.mc_approx_mean(1000, 1e-4).mean()
             })
             .collect();
@@ -245,7 +245,7 @@ fn approximate_conditional_expectation(
     variance_samples: usize,
     error_limit: f64,
 ) -> Variance {
-    let hetero = UnfixedHeterozygosity::new(
+    let hetero = Heterozygosity::unfixed(
         population_size,
         mutation_rate,
         Selection::SkewNormal {
@@ -1,10 +1,10 @@
 use average::Variance;
 use indicatif::{ProgressBar, ProgressStyle};
 use preexplorer::prelude::*;
-use quadrature::integrate;
 use rand::prelude::*;
 use rayon::prelude::*;
 use sandpiper::{N_REDNECK, N_SANDPIPER, U};
+use statrs::distribution::Continuous;
 
 fn main() {
     // Computing the normalizing constant C_{s, \beta}.
@@ -15,15 +15,13 @@ fn main() {
         // Computing
         //s >= 0 and N_REDNECK
         let h = |s: f64| 1. / (1. + (-beta * s).exp());
-        let integrand = |x: f64| -> f64 {
-            (2. * N_REDNECK as f64 * s * (x.powi(2) + 2. * h(s) * x * (1. - x) - 1.)).exp()
-                * x.powf(4. * N_REDNECK as f64 * U - 1.)
-                * (1. - x).powf(4. * N_REDNECK as f64 * U - 1.)
-        };
-        let o = integrate(integrand, 0.0, 1.0, 1e-6);
+        let gen_freq = sandpiper::GeneticFreq::new(N_REDNECK, U, s, h(s)).unwrap();
+        // Normalizing constant of the density shifted by `2Ns`, computed in log space
+        let normalizing_constant =
+            (gen_freq.ln_normalizing_constant() - 2. * N_REDNECK as f64 * s).exp();
         // Plotting
-        let grid = ndarray::Array::linspace(0., 1., 100);
-        (&grid, grid.iter().map(|x| integrand(*x)))
+        let grid: Vec<f64> = (0..100).map(|i| (i as f64 + 0.5) / 100.).collect();
+        (&grid, grid.iter().map(|x| gen_freq.pdf(*x)))
             .preexplore()
             .plot("testing")
             .unwrap();
@@ -32,12 +30,11 @@ fn main() {
         let reference = 83.25708017910746;
         println!("refernce: {:?}", reference);
         println!(
-            "direct integral: {:?}",
-            grid.iter().map(|x| integrand(*x)).sum::<f64>() / 100.
+            "total probability: {:?}",
+            grid.iter().map(|x| gen_freq.pdf(*x)).sum::<f64>() / 100.
         );
-        println!("{:?}", o.integral);
-        println!("There are problems when evaluation close to the borders!!");
-        assert!((o.integral - reference).abs() <= 1e-6);
+        println!("{:?}", normalizing_constant);
+        assert!((normalizing_constant - reference).abs() <= 1e-6);
     }
     // One Monte Carlo approximation
     if false {
@@ -1,23 +1,14 @@
 # Sandpiper
-N = 2 * 10**4
-U = 1.2 * 10**(-8)
+# Density saved by the example genetic_freq, with GeneticFreq::pdf
 beta = 0
 s = 0.001
 
-h = 1 / (1 + exp(-beta * s))
-
-g(x) = exp(2 * N * s * ( x*x + 2 *h *x *(1 - x) - (s >= 0))) * x**(4 * N * U - 1) * (1 - x)**(4 * N * U - 1)
-
-
 set xrange [0:1]
 set xlabel "frequency, x"
-set ylabel "unnormalized density"
+set ylabel "density"
 
 set title sprintf("Sandpiper, beta = %.f", beta)
 
-plot g(x) title sprintf("s = %.f", s)
-#g(x, 0) title "s = 0",\
-#	g(x, 0.001) title "s = 0.001",\
-#	g(x, -0.001) title "s = -0.001"
+plot "target/preexplorer/data/genetic_frequency_density.txt" using 1:2 with lines title sprintf("s = %g", s)
 
 pause -1
\ No newline at end of file
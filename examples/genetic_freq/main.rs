@@ -3,6 +3,7 @@ use preexplorer::prelude::*;
 use rand::distributions::Distribution;
 use rayon::prelude::*;
 use sandpiper::prelude::*;
+use statrs::distribution::Continuous;
 
 const SAMPLES: usize = 10_000; // _000;
 
@@ -28,6 +29,27 @@ fn main() -> Result<()> {
         println!("Mean: {}", mean);
     }
 
+    // Fixed parameter genetic frequency
+    // Saving density, plotted by genetic_frequency_density.gnu
+    if false {
+        // Parameters
+        let population = N_SANDPIPER;
+        let mutation_rate = U;
+        let selection = 0.001;
+        let beta: f64 = 0.;
+        // Random variable
+        let dominance = 1. / (1. + (-beta * selection).exp());
+        let gen_freq = GeneticFreq::new(population, mutation_rate, selection, dominance)?;
+        // Density at the midpoints of a grid, since it is unbounded at 0 and 1 when 4NU < 1
+        let grid: Vec<f64> = (0..1000).map(|i| (i as f64 + 0.5) / 1000.).collect();
+        let density: Vec<f64> = grid.iter().map(|x| gen_freq.pdf(*x)).collect();
+        // Reporting
+        (&grid, &density)
+            .preexplore()
+            .set_title(format!("Sandpiper, beta = {}, s = {}", beta, selection))
+            .save_with_id("genetic_frequency_density")?;
+    }
+
     // Fixed parameter genetic frequency
     // Plotting and saving histogram
     if false {
@@ -254,7 +276,7 @@ fn main() -> Result<()> {
             .unwrap();
     }
 
-    // One genetic frequency for the UnfixedHeterozygosity
+    // One genetic frequency for the unfixed Heterozygosity
     // Plotting
     if false {
         // Parameters
@@ -264,7 +286,7 @@ fn main() -> Result<()> {
         let beta: f64 = 0.;
         // Random variable
         let dominance = 1. / (1. + (-beta * selection).exp() as f64);
-        let hetero = UnfixedHeterozygosity::new(
+        let hetero = Heterozygosity::unfixed(
             population,
             mutation_rate,
             sandpiper::Selection::Fixed(selection),
@@ -282,7 +304,7 @@ fn main() -> Result<()> {
             .unwrap();
     }
 
-    // Various genetic frequencies and betas for the UnfixedHeterozygosity
+    // Various genetic frequencies and betas for the unfixed Heterozygosity
     // Plotting
     if false {
         // Parameters
@@ -295,7 +317,7 @@ fn main() -> Result<()> {
         for selection in selections {
             for beta in &betas {
                 let dominance = 1. / (1. + (-beta * selection).exp() as f64);
-                let hetero = UnfixedHeterozygosity::new(
+                let hetero = Heterozygosity::unfixed(
                     population,
                     mutation_rate,
                     sandpiper::Selection::Fixed(selection),
@@ -104,7 +104,7 @@ alpha = 0 (ie no skewness, just a normal gaussian distribution)."
         grid_initial.extend(grid_geometric.clone().into_iter().map(|mu| mu.abs()));
 
         let conditions_initial = grid_initial.iter().map(|&location| {
-            let hetero = UnfixedHeterozygosity::new(
+            let hetero = Heterozygosity::unfixed(
                 population_size,
                 sandpiper::U,
                 Selection::Fixed(location),
@@ -11,7 +11,7 @@ fn main() -> anyhow::Result<()> {
         let beta = 0.0; // 3_000.0;
         let upper_bound = UpperBound::Smallest;
         // Random variable
-        let hetero = UnfixedHeterozygosity::new(
+        let hetero = Heterozygosity::unfixed(
             population_size,
             mutation_rate,
             Selection::Fixed(selection),
@@ -48,7 +48,7 @@ fn main() -> anyhow::Result<()> {
         ];
         for upper_bound in upper_bounds {
             // Random variable
-            let hetero = UnfixedHeterozygosity::new(
+            let hetero = Heterozygosity::unfixed(
                 population_size,
                 mutation_rate,
                 Selection::Fixed(selection),
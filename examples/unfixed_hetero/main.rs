@@ -2,7 +2,7 @@ use preexplorer::prelude::*;
 use sandpiper::prelude::*;
 
 fn main() -> anyhow::Result<()> {
-    // Density of genetic frequency for the UnfixedHeterozygosity
+    // Density of genetic frequency for the unfixed Heterozygosity
     // Plotting
     if false {
         //Paramteres
@@ -11,7 +11,7 @@ fn main() -> anyhow::Result<()> {
         let selection: f64 = 5e-1;
         let rate: f64 = 3000.;
         // Random variable
-        let hetero = UnfixedHeterozygosity::new(
+        let hetero = Heterozygosity::unfixed(
             population,
             mutation_rate,
             Selection::Fixed(selection),
@@ -51,7 +51,7 @@ fn main() -> anyhow::Result<()> {
         let selection = -0.0;
         let beta = 3_000.0;
         // Random variable
-        let hetero = UnfixedHeterozygosity::new(
+        let hetero = Heterozygosity::unfixed(
             population_size,
             mutation_rate,
             Selection::Fixed(selection),
@@ -80,7 +80,7 @@ fn main() -> anyhow::Result<()> {
             println!("{:?}", chrono::offset::Local::now());
             for beta in &betas {
                 // Random variable
-                let hetero = UnfixedHeterozygosity::new(
+                let hetero = Heterozygosity::unfixed(
                     population_size,
                     mutation_rate,
                     Selection::Fixed(selection),
@@ -117,7 +117,7 @@ fn main() -> anyhow::Result<()> {
         let beta = 3_000.0;
         let upper_bound = UpperBound::Midpoint;
         // Random variable
-        let hetero = UnfixedHeterozygosity::new(
+        let hetero = Heterozygosity::unfixed(
             population_size,
             mutation_rate,
             Selection::SkewNormal {
@@ -16,7 +16,8 @@ fn main() {
 
         let alpha = -100.0;
         let parameters = Parameters::new(mu, sigma, alpha, beta).unwrap();
-        let subs = Substitutions::new(N_SANDPIPER, parameters);
+        let subs = Substitutions::new(N_SANDPIPER, parameters.selection(), parameters.dominance())
+            .unwrap();
 
         println!("{:?}", subs.mean());
     }
@@ -36,7 +37,9 @@ fn main() {
             .iter()
             .map(|&alpha| {
                 let parameters = Parameters::new(mu, sigma, alpha, beta).unwrap();
-                let subs = Substitutions::new(N_SANDPIPER, parameters);
+                let subs =
+                    Substitutions::new(N_SANDPIPER, parameters.selection(), parameters.dominance())
+                        .unwrap();
                 (subs.mean() - EMPIRICAL_MEAN_SUBSTITUTIONS_SANDPIPER)
                     .abs()
                     .into()
@@ -59,7 +62,12 @@ fn main() {
                 .iter()
                 .map(|&alpha| {
                     let parameters = Parameters::new(mu, *sigma, alpha, beta).unwrap();
-                    let subs = Substitutions::new(N_SANDPIPER, parameters);
+                    let subs = Substitutions::new(
+                        N_SANDPIPER,
+                        parameters.selection(),
+                        parameters.dominance(),
+                    )
+                    .unwrap();
                     (subs.mean() - EMPIRICAL_MEAN_SUBSTITUTIONS_SANDPIPER)
                         .abs()
                         .into()
@@ -95,7 +103,9 @@ fn main() {
             .iter()
             .map(|&alpha| {
                 let parameters = Parameters::new(mu, sigma, alpha, beta).unwrap();
-                let subs = Substitutions::new(N_SANDPIPER, parameters);
+                let subs =
+                    Substitutions::new(N_SANDPIPER, parameters.selection(), parameters.dominance())
+                        .unwrap();
                 (subs.mean() - EMPIRICAL_MEAN_SUBSTITUTIONS_SANDPIPER)
                     .abs()
                     .into()
@@ -121,7 +131,12 @@ fn main() {
                 .iter()
                 .map(|&alpha| {
                     let parameters = Parameters::new(mu, sigma, alpha, *beta).unwrap();
-                    let subs = Substitutions::new(N_SANDPIPER, parameters);
+                    let subs = Substitutions::new(
+                        N_SANDPIPER,
+                        parameters.selection(),
+                        parameters.dominance(),
+                    )
+                    .unwrap();
                     (subs.mean() - EMPIRICAL_MEAN_SUBSTITUTIONS_SANDPIPER)
                         .abs()
                         .into()
@@ -171,7 +186,9 @@ fn fitting_inspection(beta: f64) {
         .map(|(mu, sigma)| {
             let alpha_fitting = |alpha: f64| -> f64 {
                 let parameters = Parameters::new(*mu, *sigma, alpha, beta).unwrap();
-                let subs = Substitutions::new(N_SANDPIPER, parameters);
+                let subs =
+                    Substitutions::new(N_SANDPIPER, parameters.selection(), parameters.dominance())
+                        .unwrap();
                 (subs.mean() - EMPIRICAL_MEAN_SUBSTITUTIONS_SANDPIPER)
                     .abs()
                     .into()
@@ -1,10 +1,8 @@
 pub use self::beta::Beta;
 pub use self::genetic_freq::GeneticFreq;
-pub use self::heterozygosity::{
-    Dominance, Heterozygosity, Selection, UnfixedHeterozygosity, UpperBound,
-};
+pub use self::heterozygosity::{Dominance, Heterozygosity, Selection, UpperBound};
 pub use self::normal::Normal;
-pub use self::skew_normal::SkewNormal;
+pub use self::skew_normal::{SkewNormal, SkewNormalFit};
 
 mod beta;
 mod genetic_freq;
@@ -12,6 +10,39 @@ mod heterozygosity;
 mod normal;
 mod skew_normal;
 
+/// Probability of each tail of a skew-normal selection left out of the integration range of `Selection::average`.
+pub(crate) const SELECTION_TAIL: f64 = 1e-12;
+
+/// Maximum number of proposals outside the window before `sample_window` falls back to inverse transform.
+pub(crate) const MAX_WINDOW_REJECTIONS: usize = 16;
+
+/// Maximum number of iterations when inverting a distribution function.
+pub(crate) const MAX_INVERSE_ITERATIONS: usize = 200;
+
+/// Samples conditioned on a window by rejecting proposals outside it.
+///
+/// Proposals for which `inside` fails are rejected up to `MAX_WINDOW_REJECTIONS` times,
+/// after which `inverse` is called with a standard uniform random variable
+/// to obtain the sample by inverse transform within the window,
+/// so that windows with little probability do not stall sampling.
+pub(crate) fn sample_window<R, T>(
+    rng: &mut R,
+    mut propose: impl FnMut(&mut R) -> T,
+    inside: impl Fn(&T) -> bool,
+    inverse: impl FnOnce(f64) -> T,
+) -> T
+where
+    R: rand::Rng + ?Sized,
+{
+    for _ in 0..MAX_WINDOW_REJECTIONS {
+        let proposal = propose(rng);
+        if inside(&proposal) {
+            return proposal;
+        }
+    }
+    inverse(rng.sample(rand_distr::Standard))
+}
+
 pub mod helper {
 
     use rayon::prelude::*;
@@ -108,7 +139,7 @@ pub mod helper {
     ///
     /// By the nature of the computation, the result is random. A proper error analysis can guarantee probabilistic bounds.
     ///
-    /// The rng used is `thread_rng`.
+    /// The seed is drawn from `thread_rng`. See `par_approx_histogram_seeded` for reproducible results.
     ///
     /// # Examples
     ///
@@ -120,17 +151,54 @@ pub mod helper {
         init_samples: usize,
         repeteitions: usize,
         error: f64,
+    ) -> anyhow::Result<quantiles::histogram::Histogram<f64>> {
+        let seed = rand::Rng::gen(&mut rand::thread_rng());
+        par_approx_histogram_seeded(variable, grid, init_samples, repeteitions, error, seed)
+    }
+
+    /// Approximates the cummulative distribution of a random variable with parallel sampling,
+    /// reproducibly from `seed`.
+    ///
+    /// # Algorithm
+    ///
+    /// Same as `par_approx_histogram`, where each simulation draws its samples from `stream_rng`.
+    ///
+    /// # Remarks
+    ///
+    /// The result only depends on the seed, not on the number of threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::distribution::helper::par_approx_histogram_seeded;
+    ///
+    /// let variable = rand_distr::Uniform::new(0., 1.);
+    /// let grid = vec![0., 0.5, 1.];
+    /// let histo = par_approx_histogram_seeded(variable, grid.clone(), 1000, 2, 0.1, 7).unwrap();
+    /// let other = par_approx_histogram_seeded(variable, grid, 1000, 2, 0.1, 7).unwrap();
+    /// assert_eq!(histo.count(), other.count());
+    /// assert_eq!(
+    ///     histo.iter().map(|(_, count)| *count).collect::<Vec<usize>>(),
+    ///     other.iter().map(|(_, count)| *count).collect::<Vec<usize>>(),
+    /// );
+    /// ```
+    pub fn par_approx_histogram_seeded(
+        variable: impl rand_distr::Distribution<f64> + Sync,
+        grid: Vec<f64>,
+        init_samples: usize,
+        repeteitions: usize,
+        error: f64,
+        seed: u64,
     ) -> anyhow::Result<quantiles::histogram::Histogram<f64>> {
         let mut samples = init_samples;
+        // Each simulation has its own seed: one for the first histogram and one per repetition in each round
+        let mut simulation_seeds = (0..).map(|index| sub_seed(seed, index));
         loop {
             println!("Trying {} samples", samples);
             // First histogram
             // Simulation
             let mut init_histo = quantiles::histogram::Histogram::<f64>::new(grid.clone()).unwrap();
-            let simulation: Vec<f64> = (0..samples)
-                .into_par_iter()
-                .map(|_| variable.sample(&mut rand::thread_rng()))
-                .collect();
+            let simulation = par_sample(&variable, samples, simulation_seeds.next().unwrap());
             for value in simulation {
                 init_histo.insert(value);
             }
@@ -142,34 +210,35 @@ pub mod helper {
                 })
                 .collect::<Vec<f64>>();
 
-            let result = (0..repeteitions).into_par_iter().find_any(|_| {
-                // Other histogram
-                let mut other_histo =
-                    quantiles::histogram::Histogram::<f64>::new(grid.clone()).unwrap();
-                let simulation: Vec<f64> = (0..samples)
-                    .into_par_iter()
-                    .map(|_| variable.sample(&mut rand::thread_rng()))
-                    .collect();
-                for value in simulation {
-                    other_histo.insert(value);
-                }
-                // Distribution
-                let other_distribution = (0..grid.len())
-                    .map(|i| {
-                        other_histo.total_below(quantiles::histogram::Bound::Finite(grid[i])) as f64
-                            / samples as f64
-                    })
-                    .collect::<Vec<f64>>();
-                // Checking correctness
-                let empirical_error = (0..grid.len())
-                    .map(|i| (init_distribution[i] - other_distribution[i]).abs())
-                    .map(|x| ordered_float::NotNan::new(x).unwrap())
-                    .max()
-                    .unwrap()
-                    .into_inner();
-                // Checking
-                empirical_error > error
-            });
+            let repetition_seeds: Vec<u64> = simulation_seeds.by_ref().take(repeteitions).collect();
+            let result = repetition_seeds
+                .into_par_iter()
+                .find_any(|repetition_seed| {
+                    // Other histogram
+                    let mut other_histo =
+                        quantiles::histogram::Histogram::<f64>::new(grid.clone()).unwrap();
+                    let simulation = par_sample(&variable, samples, *repetition_seed);
+                    for value in simulation {
+                        other_histo.insert(value);
+                    }
+                    // Distribution
+                    let other_distribution = (0..grid.len())
+                        .map(|i| {
+                            other_histo.total_below(quantiles::histogram::Bound::Finite(grid[i]))
+                                as f64
+                                / samples as f64
+                        })
+                        .collect::<Vec<f64>>();
+                    // Checking correctness
+                    let empirical_error = (0..grid.len())
+                        .map(|i| (init_distribution[i] - other_distribution[i]).abs())
+                        .map(|x| ordered_float::NotNan::new(x).unwrap())
+                        .max()
+                        .unwrap()
+                        .into_inner();
+                    // Checking
+                    empirical_error > error
+                });
             match result {
                 Some(_) => samples *= 2,
                 None => break,
@@ -177,13 +246,180 @@ pub mod helper {
         }
         // Final simulation
         let mut histo = quantiles::histogram::Histogram::<f64>::new(grid.clone()).unwrap();
-        let simulation: Vec<f64> = (0..samples)
-            .into_par_iter()
-            .map(|_| variable.sample(&mut rand::thread_rng()))
-            .collect();
+        let simulation = par_sample(&variable, samples, simulation_seeds.next().unwrap());
         for value in simulation {
             histo.insert(value);
         }
         Ok(histo)
     }
+
+    /// Returns `samples` realizations of `variable` in parallel, where the `i`-th one
+    /// is drawn from `stream_rng(seed, i)`.
+    ///
+    /// The result only depends on the seed, not on the number of threads.
+    pub fn par_sample(
+        variable: &(impl rand_distr::Distribution<f64> + Sync),
+        samples: usize,
+        seed: u64,
+    ) -> Vec<f64> {
+        (0..samples as u64)
+            .into_par_iter()
+            .map(|i| variable.sample(&mut stream_rng(seed, i)))
+            .collect()
+    }
+
+    /// Returns the random number generator for the given stream of `seed`.
+    ///
+    /// # Remarks
+    ///
+    /// Streams are counter based: each one is a PCG generator whose state is a hash of the seed and the stream,
+    /// and whose increment is the stream itself. Therefore, each unit of work in a parallel computation
+    /// can own a stream, making the result independent of the number of threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::Rng;
+    /// use sandpiper::distribution::helper::stream_rng;
+    ///
+    /// let x: f64 = stream_rng(42, 3).gen();
+    /// let y: f64 = stream_rng(42, 3).gen();
+    /// assert_eq!(x, y);
+    /// ```
+    pub fn stream_rng(seed: u64, stream: u64) -> rand_pcg::Pcg32 {
+        rand_pcg::Pcg32::new(sub_seed(seed, stream), stream)
+    }
+
+    /// Derives the seed of the given index from `seed`, so that nested computations
+    /// can be seeded independently from one seed.
+    pub fn sub_seed(seed: u64, index: u64) -> u64 {
+        splitmix64(seed ^ splitmix64(index))
+    }
+
+    /// Finalizer of the SplitMix64 generator, a bijective hash with good avalanche.
+    fn splitmix64(x: u64) -> u64 {
+        let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Finds the root of an increasing function in `[lower, upper]`, starting from `start`.
+    ///
+    /// `value_and_derivative` returns the value of the function at a point and its derivative there.
+    ///
+    /// # Algorithm
+    ///
+    /// Newton's method, safeguarded by bisection whenever a step leaves the current bracket,
+    /// until a step is below the machine precision relative to the iterate
+    /// or after `MAX_INVERSE_ITERATIONS` iterations.
+    pub(crate) fn safeguarded_newton<F>(
+        mut value_and_derivative: F,
+        (mut lower, mut upper): (f64, f64),
+        start: f64,
+    ) -> f64
+    where
+        F: FnMut(f64) -> (f64, f64),
+    {
+        let mut current = start;
+        for _ in 0..super::MAX_INVERSE_ITERATIONS {
+            let (value, derivative) = value_and_derivative(current);
+            if value > 0. {
+                upper = current;
+            } else if value < 0. {
+                lower = current;
+            } else {
+                break;
+            }
+            let mut next = current - value / derivative;
+            if !(next > lower && next < upper) {
+                next = (lower + upper) * 0.5;
+            }
+            let step = (next - current).abs();
+            current = next;
+            if step <= f64::EPSILON * current.abs() {
+                break;
+            }
+        }
+        current
+    }
+
+    /// Integrates `f` over consecutive breakpoints up to a relative error.
+    ///
+    /// Since quadrature targets an absolute error, the integral is computed a second time
+    /// after dividing `f` by the first estimate.
+    pub(crate) fn relative_integral<F>(f: F, breakpoints: &[f64]) -> quadrature::Output
+    where
+        F: Fn(f64) -> f64,
+    {
+        let piecewise = |scale: f64| {
+            breakpoints.windows(2).fold(
+                quadrature::Output {
+                    integral: 0.,
+                    error_estimate: 0.,
+                    num_function_evaluations: 0,
+                },
+                |total, window| {
+                    let piece = quadrature::integrate(
+                        |s| f(s) / scale,
+                        window[0],
+                        window[1],
+                        crate::constants::EPS,
+                    );
+                    quadrature::Output {
+                        integral: total.integral + piece.integral,
+                        error_estimate: total.error_estimate + piece.error_estimate,
+                        num_function_evaluations: total.num_function_evaluations
+                            + piece.num_function_evaluations,
+                    }
+                },
+            )
+        };
+        let first = piecewise(1.);
+        let scale = first.integral;
+        if !(scale > 0. && scale.is_finite()) {
+            return first;
+        }
+        let second = piecewise(scale);
+        quadrature::Output {
+            integral: scale * second.integral,
+            error_estimate: scale * second.error_estimate,
+            num_function_evaluations: first.num_function_evaluations
+                + second.num_function_evaluations,
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn par_sample_independent_of_threads() {
+            let variable = rand_distr::Exp1;
+            let with_threads = |threads| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap()
+                    .install(|| par_sample(&variable, 10_000, 5))
+            };
+            assert_eq!(with_threads(1), with_threads(4));
+            assert_ne!(par_sample(&variable, 100, 5), par_sample(&variable, 100, 6));
+        }
+
+        #[test]
+        fn streams_differ() {
+            use rand::Rng;
+
+            let first: Vec<u32> = stream_rng(0, 0)
+                .sample_iter(rand::distributions::Standard)
+                .take(4)
+                .collect();
+            let second: Vec<u32> = stream_rng(0, 1)
+                .sample_iter(rand::distributions::Standard)
+                .take(4)
+                .collect();
+            assert_ne!(first, second);
+        }
+    }
 }
@@ -1,7 +1,17 @@
+// Traits
 use rand::Rng;
 use rand_distr::Float;
 use rand_distr::{Distribution, Open01};
-use std::{error, fmt};
+use statrs::distribution::{CheckedInverseCDF, Continuous, InverseCDF, Univariate};
+use statrs::statistics::{Entropy, Max, Mean, Min, Mode, Skewness, Variance};
+
+// Structs
+use crate::error::{Result, StatsError};
+
+// Functions
+use super::helper::safeguarded_newton;
+use statrs::function::beta::{beta_reg, ln_beta};
+use statrs::function::gamma::digamma;
 
 /// The algorithm used for sampling the Beta distribution.
 ///
@@ -40,11 +50,13 @@ struct BC<N> {
 /// # Example
 ///
 /// ```
-/// use rand_distr::{Distribution, Beta};
+/// use rand::distributions::Distribution;
+/// use sandpiper::Beta;
+/// use statrs::distribution::Continuous;
 ///
 /// let beta = Beta::new(2.0, 5.0).unwrap();
 /// let v = beta.sample(&mut rand::thread_rng());
-/// println!("{} is from a Beta(2, 5) distribution", v);
+/// println!("{} is from a Beta(2, 5) distribution with density {}", v, beta.pdf(v));
 /// ```
 #[derive(Clone, Copy, Debug)]
 pub struct Beta<N> {
@@ -54,38 +66,31 @@ pub struct Beta<N> {
     algorithm: BetaAlgorithm<N>,
 }
 
-/// Error type returned from `Beta::new`.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum BetaError {
-    /// `alpha <= 0` or `nan`.
-    AlphaTooSmall,
-    /// `beta <= 0` or `nan`.
-    BetaTooSmall,
-}
-
-impl fmt::Display for BetaError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(match self {
-            BetaError::AlphaTooSmall => "alpha is not positive in beta distribution",
-            BetaError::BetaTooSmall => "beta is not positive in beta distribution",
-        })
-    }
-}
-
-impl error::Error for BetaError {}
-
 impl<N: Float> Beta<N>
 where
     Open01: Distribution<N>,
 {
     /// Construct an object representing the `Beta(alpha, beta)`
     /// distribution.
-    pub fn new(alpha: N, beta: N) -> Result<Beta<N>, BetaError> {
+    ///
+    /// # Errors
+    ///
+    /// Returns `ArgMustBePositive` if `alpha` or `beta` are not positive or `NaN`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::Beta;
+    ///
+    /// assert!(Beta::new(0.5, 2.0).is_ok());
+    /// assert!(Beta::new(0.0, 2.0).is_err());
+    /// ```
+    pub fn new(alpha: N, beta: N) -> Result<Beta<N>> {
         if !(alpha > N::from(0.)) {
-            return Err(BetaError::AlphaTooSmall);
+            return Err(StatsError::ArgMustBePositive("alpha"));
         }
         if !(beta > N::from(0.)) {
-            return Err(BetaError::BetaTooSmall);
+            return Err(StatsError::ArgMustBePositive("beta"));
         }
         // From now on, we use the notation from the reference,
         // i.e. `alpha` and `beta` are renamed to `a0` and `b0`.
@@ -95,7 +100,9 @@ where
         } else {
             (b0, a0, true)
         };
-        if alpha > N::from(1.) {
+        // Algorithm BB requires both shapes above one, and BC the larger shape first,
+        // which selecting by `alpha` alone missed whenever one shape is below one
+        if a > N::from(1.) {
             let alpha = a + b;
             let beta = ((alpha - N::from(2.)) / (N::from(2.) * a * b - alpha)).sqrt();
             let gamma = a + N::from(1.) / beta;
@@ -107,6 +114,8 @@ where
                 algorithm: BetaAlgorithm::BB(BB { alpha, beta, gamma }),
             })
         } else {
+            // Here `a` is the maximum instead of the minimum.
+            let (a, b, switched_params) = (b, a, !switched_params);
             let alpha = a + b;
             let beta = N::from(1.) / b;
             let delta = N::from(1.) + a - b;
@@ -128,6 +137,56 @@ where
             })
         }
     }
+
+    /// Returns the shape parameters `(alpha, beta)`.
+    pub fn shapes(&self) -> (N, N) {
+        if self.switched_params {
+            (self.b, self.a)
+        } else {
+            (self.a, self.b)
+        }
+    }
+}
+
+impl Beta<f64> {
+    /// Samples `(ln x, ln(1 - x))` for `x` following the Beta distribution.
+    ///
+    /// Both logarithms are accurate even when `x` or `1 - x` are too small to be represented,
+    /// as happens often for shapes far below one, where `sample` returns exactly `0.0` or `1.0`.
+    ///
+    /// # Algorithm
+    ///
+    /// `x = G_alpha / (G_alpha + G_beta)` for independent Gamma variables with shapes `alpha` and `beta`,
+    /// whose logarithms are sampled by `sample_ln_gamma`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let beta = sandpiper::Beta::new(1e-3, 1e-3).unwrap();
+    /// let (ln_x, ln_y) = beta.sample_ln(&mut rand::thread_rng());
+    /// assert!(ln_x < 0. && ln_y <= 0. || ln_x <= 0. && ln_y < 0.);
+    /// ```
+    pub fn sample_ln<R: Rng + ?Sized>(&self, rng: &mut R) -> (f64, f64) {
+        let (alpha, beta) = self.shapes();
+        let ln_x = sample_ln_gamma(alpha, rng);
+        let ln_y = sample_ln_gamma(beta, rng);
+        let ln_sum = ln_x.max(ln_y) + (-(ln_x - ln_y).abs()).exp().ln_1p();
+        (ln_x - ln_sum, ln_y - ln_sum)
+    }
+}
+
+/// Samples the logarithm of a Gamma variable with shape `shape` and unit scale.
+///
+/// For `shape < 1`, it uses that `G_shape = G_(shape + 1) * U^(1 / shape)` for a uniform `U`,
+/// so that the logarithm is accurate even when the variable underflows.
+pub(crate) fn sample_ln_gamma<R: Rng + ?Sized>(shape: f64, rng: &mut R) -> f64 {
+    if shape < 1. {
+        let u: f64 = rng.sample(Open01);
+        let gamma = rand_distr::Gamma::new(shape + 1., 1.).unwrap();
+        gamma.sample(rng).ln() + u.ln() / shape
+    } else {
+        rand_distr::Gamma::new(shape, 1.).unwrap().sample(rng).ln()
+    }
 }
 
 impl<N: Float> Distribution<N> for Beta<N>
@@ -218,3 +277,405 @@ where
         }
     }
 }
+
+impl Min<f64> for Beta<f64> {
+    /// Returns the minimum value in the domain of the Beta distribution, `0`.
+    fn min(&self) -> f64 {
+        0.
+    }
+}
+
+impl Max<f64> for Beta<f64> {
+    /// Returns the maximum value in the domain of the Beta distribution, `1`.
+    fn max(&self) -> f64 {
+        1.
+    }
+}
+
+impl Univariate<f64, f64> for Beta<f64> {
+    /// Calculates the cumulative distribution function for the Beta distribution at `x`.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// I_x(alpha, beta)
+    /// ```
+    ///
+    /// where `I_x` is the regularized lower incomplete beta function.
+    fn cdf(&self, x: f64) -> f64 {
+        let (alpha, beta) = self.shapes();
+        if x <= 0. {
+            0.
+        } else if x >= 1. {
+            1.
+        } else {
+            beta_reg(alpha, beta, x)
+        }
+    }
+}
+
+impl InverseCDF<f64> for Beta<f64> {
+    /// Calculates the inverse cumulative distribution function for the Beta distribution at `p`.
+    ///
+    /// # Panics
+    ///
+    /// If `p < 0.0` or `p > 1.0`
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        self.checked_inverse_cdf(p).unwrap()
+    }
+}
+
+impl CheckedInverseCDF<f64> for Beta<f64> {
+    /// Calculates the inverse cumulative distribution function for the Beta distribution at `p`.
+    ///
+    /// # Algorithm
+    ///
+    /// Quantiles below the median are found by `lower_quantile`,
+    /// and those above it as one minus the quantiles of `Beta(beta, alpha)`,
+    /// so that both tails are resolved close to their extreme.
+    ///
+    /// # Errors
+    ///
+    /// If `p < 0.0` or `p > 1.0`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::Beta;
+    /// use statrs::distribution::{InverseCDF, Univariate};
+    ///
+    /// let beta = Beta::new(1e-3, 1e-3).unwrap();
+    /// let x = beta.inverse_cdf(0.45);
+    /// assert!(x > 0. && x < 1e-40);
+    /// assert!((beta.cdf(x) - 0.45).abs() < 1e-12);
+    /// ```
+    fn checked_inverse_cdf(&self, p: f64) -> Result<f64> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(StatsError::ArgIntervalIncl("p", 0., 1.));
+        }
+        let (alpha, beta) = self.shapes();
+        Ok(if p == 0. {
+            0.
+        } else if p == 1. {
+            1.
+        } else if p <= beta_reg(alpha, beta, 0.5) {
+            lower_quantile(alpha, beta, p)
+        } else {
+            1. - lower_quantile(beta, alpha, 1. - p)
+        })
+    }
+}
+
+/// Finds `t` in `[0, 0.5]` with `I_t(alpha, beta) = p`, for `p` at most the mass of `[0, 0.5]`.
+///
+/// # Algorithm
+///
+/// Newton's method on `ln I_t` as a function of `ln t`, which is nearly linear close to zero,
+/// starting from the approximation `I_t ~ t^alpha / (alpha B(alpha, beta))`
+/// and safeguarded by bisection with `safeguarded_newton`.
+/// Returns zero if the quantile is below the smallest positive normal number.
+fn lower_quantile(alpha: f64, beta: f64, p: f64) -> f64 {
+    let ln_p = p.ln();
+    let excess = |u: f64| beta_reg(alpha, beta, u.exp()).ln() - ln_p;
+    let (lower, upper) = (f64::MIN_POSITIVE.ln(), 0.5_f64.ln());
+    if excess(lower) >= 0. {
+        return 0.;
+    }
+    let start = ((ln_p + alpha.ln() + ln_beta(alpha, beta)) / alpha)
+        .max(lower)
+        .min(upper);
+    let value_and_derivative = |u: f64| {
+        let value = excess(u);
+        let ln_cdf = value + ln_p;
+        (
+            value,
+            (u + ln_pdf_unchecked(alpha, beta, u.exp()) - ln_cdf).exp(),
+        )
+    };
+    safeguarded_newton(value_and_derivative, (lower, upper), start).exp()
+}
+
+impl Mean<f64> for Beta<f64> {
+    /// Returns the mean of the Beta distribution, `alpha / (alpha + beta)`.
+    fn mean(&self) -> f64 {
+        let (alpha, beta) = self.shapes();
+        alpha / (alpha + beta)
+    }
+}
+
+impl Variance<f64> for Beta<f64> {
+    /// Returns the variance of the Beta distribution.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// alpha beta / ((alpha + beta)^2 (alpha + beta + 1))
+    /// ```
+    fn variance(&self) -> f64 {
+        let (alpha, beta) = self.shapes();
+        let sum = alpha + beta;
+        alpha * beta / (sum * sum * (sum + 1.))
+    }
+
+    /// Returns the standard deviation of the Beta distribution.
+    fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+}
+
+impl Skewness<f64> for Beta<f64> {
+    /// Returns the skewness of the Beta distribution.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// 2 (beta - alpha) sqrt(alpha + beta + 1) / ((alpha + beta + 2) sqrt(alpha beta))
+    /// ```
+    fn skewness(&self) -> f64 {
+        let (alpha, beta) = self.shapes();
+        let sum = alpha + beta;
+        2. * (beta - alpha) * (sum + 1.).sqrt() / ((sum + 2.) * (alpha * beta).sqrt())
+    }
+}
+
+impl Mode<f64> for Beta<f64> {
+    /// Returns the mode of the Beta distribution.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// (alpha - 1) / (alpha + beta - 2)
+    /// ```
+    ///
+    /// if both shapes are above one. Otherwise, the density is largest or unbounded at an extreme:
+    /// `0` if `alpha < beta` and `1` if `alpha > beta`. For equal shapes, it is `0.5` in the uniform case
+    /// and `0` when the density is unbounded at both extremes.
+    fn mode(&self) -> f64 {
+        let (alpha, beta) = self.shapes();
+        if alpha > 1. && beta > 1. {
+            (alpha - 1.) / (alpha + beta - 2.)
+        } else if alpha < beta {
+            0.
+        } else if alpha > beta {
+            1.
+        } else if alpha == 1. {
+            0.5
+        } else {
+            0.
+        }
+    }
+}
+
+impl Entropy<f64> for Beta<f64> {
+    /// Returns the differential entropy of the Beta distribution.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// ln(B(alpha, beta)) - (alpha - 1) ψ(alpha) - (beta - 1) ψ(beta) + (alpha + beta - 2) ψ(alpha + beta)
+    /// ```
+    ///
+    /// where `B` is the beta function and `ψ` is the digamma function.
+    fn entropy(&self) -> f64 {
+        let (alpha, beta) = self.shapes();
+        ln_beta(alpha, beta) - (alpha - 1.) * digamma(alpha) - (beta - 1.) * digamma(beta)
+            + (alpha + beta - 2.) * digamma(alpha + beta)
+    }
+}
+
+impl Continuous<f64, f64> for Beta<f64> {
+    /// Calculates the probability density function for the Beta distribution at `x`.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// x^(alpha - 1) (1 - x)^(beta - 1) / B(alpha, beta)
+    /// ```
+    ///
+    /// where `B` is the beta function. It is zero outside `[0, 1]`.
+    fn pdf(&self, x: f64) -> f64 {
+        self.ln_pdf(x).exp()
+    }
+
+    /// Calculates the log probability density function for the Beta distribution at `x`.
+    ///
+    /// It is computed with `ln(1 - x)` accurate for small `x`,
+    /// so that densities with tiny shapes can be evaluated close to the extremes.
+    fn ln_pdf(&self, x: f64) -> f64 {
+        let (alpha, beta) = self.shapes();
+        ln_pdf_unchecked(alpha, beta, x)
+    }
+}
+
+/// Logarithm of the Beta density at `x`, with powers of zero at the extremes taken as one.
+fn ln_pdf_unchecked(alpha: f64, beta: f64, x: f64) -> f64 {
+    if !(0.0..=1.0).contains(&x) {
+        return f64::NEG_INFINITY;
+    }
+    let power = |exponent: f64, ln_base: f64| {
+        if exponent == 0. {
+            0.
+        } else {
+            exponent * ln_base
+        }
+    };
+    power(alpha - 1., x.ln()) + power(beta - 1., (-x).ln_1p()) - ln_beta(alpha, beta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(1e-3, 1e-3; "tiny shapes")]
+    #[test_case(1e-3, 2.; "tiny alpha")]
+    #[test_case(0.5, 3.; "moderate shapes")]
+    fn sample_ln(alpha: f64, beta: f64) {
+        let distribution = Beta::new(alpha, beta).unwrap();
+        let mut rng = crate::tests::rng(5);
+        let samples = 100_000;
+        let realizations: Vec<(f64, f64)> = (0..samples)
+            .map(|_| distribution.sample_ln(&mut rng))
+            .collect();
+        assert!(realizations
+            .iter()
+            .all(|&(ln_x, ln_y)| ln_x.is_finite() && ln_y.is_finite()));
+        // Both logarithms describe the same value, up to the rounding of logarithms of Gamma variables of order -1 / alpha
+        assert!(realizations
+            .iter()
+            .all(|&(ln_x, ln_y)| (ln_x.exp() + ln_y.exp() - 1.).abs() < 1e-10));
+
+        // Mean of x (1 - x), which is mostly carried by values that `sample` rounds to 0 or 1 for tiny shapes
+        let mc: average::Variance = realizations
+            .iter()
+            .map(|&(ln_x, ln_y)| (ln_x + ln_y).exp())
+            .collect();
+        let expected = alpha * beta / ((alpha + beta) * (alpha + beta + 1.));
+        println!("computed value: {:?}, expected: {:?}", mc.mean(), expected);
+        assert!((mc.mean() - expected).abs() < 4. * mc.error());
+    }
+
+    #[test_case(2., 5.; "large shapes")]
+    #[test_case(5., 2.; "large shapes switched")]
+    #[test_case(2., 0.5; "one small shape")]
+    #[test_case(0.5, 2.; "other small shape")]
+    #[test_case(0.3, 0.7; "small shapes")]
+    #[test_case(0.7, 0.3; "small shapes switched")]
+    fn sample_moments(alpha: f64, beta: f64) {
+        let distribution = Beta::new(alpha, beta).unwrap();
+        let samples = 100_000;
+        let mc: average::Variance = distribution
+            .sample_iter(crate::tests::rng(7))
+            .take(samples)
+            .collect();
+        println!(
+            "computed value: {:?}, expected: {:?}",
+            mc.mean(),
+            distribution.mean()
+        );
+        assert!((mc.mean() - distribution.mean()).abs() < 4. * mc.error());
+        assert!(
+            (mc.sample_variance() - distribution.variance()).abs() < 2e-2 * distribution.variance()
+        );
+    }
+
+    #[test_case(2., 0.5; "alpha above one")]
+    #[test_case(0.5, 2.; "beta above one")]
+    #[test_case(0.3, 0.7; "larger beta")]
+    #[test_case(0.7, 0.3; "larger alpha")]
+    fn sample_algorithm_selection(alpha: f64, beta: f64) {
+        // Selecting the algorithm by `alpha` alone gave the wrong distribution for these shapes
+        let distribution = Beta::new(alpha, beta).unwrap();
+        let samples = 100_000;
+        let realizations: Vec<f64> = distribution
+            .sample_iter(crate::tests::rng(11))
+            .take(samples)
+            .collect();
+        assert!(realizations.iter().all(|x| (0.0..=1.0).contains(x)));
+        for &p in &[0.1, 0.25, 0.5, 0.75, 0.9] {
+            let quantile = distribution.inverse_cdf(p);
+            let below = realizations.iter().filter(|&&x| x <= quantile).count();
+            let error = (p * (1. - p) / samples as f64).sqrt();
+            assert!((below as f64 / samples as f64 - p).abs() < 4. * error);
+        }
+    }
+
+    #[test_case(2., 5.; "large shapes")]
+    #[test_case(0.5, 0.5; "small shapes")]
+    #[test_case(1e-3, 2.; "tiny alpha")]
+    fn statrs_agreement(alpha: f64, beta: f64) {
+        let distribution = Beta::new(alpha, beta).unwrap();
+        let reference = statrs::distribution::Beta::new(alpha, beta).unwrap();
+        for &x in &[1e-10, 0.1, 0.5, 0.9] {
+            assert!(
+                (distribution.ln_pdf(x) - reference.ln_pdf(x)).abs()
+                    < 1e-10 * reference.ln_pdf(x).abs().max(1.)
+            );
+            assert!((distribution.cdf(x) - reference.cdf(x)).abs() < 1e-12);
+        }
+        assert!((distribution.mean() - reference.mean()).abs() < 1e-14);
+        assert!((distribution.variance() - reference.variance()).abs() < 1e-14);
+        assert!(
+            (distribution.skewness() - reference.skewness()).abs()
+                < 1e-10 * reference.skewness().abs().max(1.)
+        );
+        assert!(
+            (distribution.entropy() - reference.entropy()).abs()
+                < 1e-10 * reference.entropy().abs().max(1.)
+        );
+    }
+
+    #[test_case(1., 1., 0., 0.; "uniform at zero")]
+    #[test_case(2., 1., 1., 2f64.ln(); "linear at one")]
+    #[test_case(0.5, 0.5, 0., f64::INFINITY; "singular")]
+    #[test_case(2., 2., 1.5, f64::NEG_INFINITY; "outside")]
+    fn ln_pdf_extremes(alpha: f64, beta: f64, x: f64, expected: f64) {
+        let distribution = Beta::new(alpha, beta).unwrap();
+        let result = distribution.ln_pdf(x);
+        if expected.is_finite() {
+            assert!((result - expected).abs() < 1e-14);
+        } else {
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test_case(2., 5.; "large shapes")]
+    #[test_case(0.3, 0.7; "small shapes")]
+    #[test_case(1e-3, 1e-3; "tiny shapes")]
+    #[test_case(5e-3, 20.; "tiny alpha")]
+    fn inverse_cdf(alpha: f64, beta: f64) {
+        let distribution = Beta::new(alpha, beta).unwrap();
+        for &p in &[1e-6, 0.01, 0.3, 0.5, 0.7, 0.99] {
+            let x = distribution.inverse_cdf(p);
+            println!("p: {:?}, x: {:?}", p, x);
+            assert!((0.0..=1.0).contains(&x));
+            if x == 0. {
+                // The quantile underflows
+                assert!(distribution.cdf(f64::MIN_POSITIVE) >= p);
+            } else if x == 1. {
+                // The quantile rounds to one
+                let reflected = Beta::new(beta, alpha).unwrap();
+                assert!(reflected.inverse_cdf(1. - p) < f64::EPSILON);
+            } else if p < 0.5 {
+                assert!((distribution.cdf(x) - p).abs() < 1e-10 * p);
+            } else {
+                assert!((distribution.cdf(x) - p).abs() < 1e-10);
+            }
+        }
+        assert!(distribution.checked_inverse_cdf(1.5).is_err());
+    }
+
+    #[test_case(2., 3., 1. / 3.; "interior")]
+    #[test_case(0.5, 2., 0.; "singular at zero")]
+    #[test_case(1., 0.5, 1.; "singular at one")]
+    #[test_case(1., 1., 0.5; "uniform")]
+    fn mode(alpha: f64, beta: f64, expected: f64) {
+        assert_eq!(Beta::new(alpha, beta).unwrap().mode(), expected);
+    }
+
+    #[test_case(0., 1.; "zero alpha")]
+    #[test_case(1., f64::NAN; "nan beta")]
+    fn new_invalid(alpha: f64, beta: f64) {
+        assert!(Beta::new(alpha, beta).is_err());
+    }
+}
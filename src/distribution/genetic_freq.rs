@@ -1,13 +1,18 @@
 // Traits
 use rand::distributions::Distribution;
 use rand::Rng;
+use statrs::distribution::{CheckedInverseCDF, Continuous, InverseCDF, Univariate};
 use statrs::statistics::{Max, Min}; // , Mean, Variance};
 
 // Structs
-use crate::distribution::Beta;
 use crate::error::{Result, StatsError};
 use std::f64;
 
+// Functions
+use super::beta::sample_ln_gamma;
+use super::helper::{relative_integral, safeguarded_newton};
+use super::sample_window;
+
 /// Distribution over allele frequency with density over [0, 1] given by
 ///
 /// `\propto \exp(2 * population * selection * (x^2 + 2 * dominance * x * (1 - x))) * (x * (1 - x))^(4 * population * mutation_rate - 1)`
@@ -29,6 +34,8 @@ pub struct GeneticFreq {
     mutation_rate: f64,
     selection: f64,
     dominance: f64,
+    /// Shift of the logarithm of the density, see `ln_maximum`.
+    ln_shift: f64,
 }
 
 impl GeneticFreq {
@@ -37,7 +44,8 @@ impl GeneticFreq {
     ///
     /// # Errors
     ///
-    /// Returns an error if `dominance` is not in the interval [0, 1]
+    /// Returns an error if `dominance` is not in the interval [0, 1],
+    /// if `selection` is `NaN`, or if `population * mutation_rate` is not positive.
     ///
     /// # Examples
     ///
@@ -62,7 +70,12 @@ impl GeneticFreq {
         selection: f64,
         dominance: f64,
     ) -> Result<Self> {
-        if dominance.is_nan() || dominance > 1.0 || dominance < 0.0 {
+        if !(0.0..=1.0).contains(&dominance)
+            || selection.is_nan()
+            || population == 0
+            || mutation_rate.is_nan()
+            || mutation_rate <= 0.0
+        {
             Err(StatsError::BadParams)
         } else {
             Ok(GeneticFreq {
@@ -70,74 +83,976 @@ impl GeneticFreq {
                 mutation_rate,
                 selection,
                 dominance,
-            })
+                ln_shift: 0.,
+            }
+            .with_ln_shift())
+        }
+    }
+
+    /// Returns the logarithm of the normalizing constant of the density, that is
+    ///
+    /// `ln \int_0^1 \exp(2 * population * selection * (x^2 + 2 * dominance * x * (1 - x))) * (x * (1 - x))^(4 * population * mutation_rate - 1) dx`
+    ///
+    /// # Remarks
+    ///
+    /// The integral is computed by numerical quadrature each time this method is called.
+    ///
+    /// # Examples
+    ///
+    /// Without selection, the constant is the beta function.
+    /// ```
+    /// use sandpiper::GeneticFreq;
+    ///
+    /// let gen_freq = GeneticFreq::new(1000, 0.00001, 0.0, 0.5).unwrap();
+    /// let shape = 4. * 1000. * 0.00001;
+    /// let expected = statrs::function::beta::ln_beta(shape, shape);
+    /// assert!((gen_freq.ln_normalizing_constant() - expected).abs() < 1e-10);
+    /// ```
+    pub fn ln_normalizing_constant(&self) -> f64 {
+        self.ln_shift + self.ln_shifted_normalizing_constant()
+    }
+
+    /// Shape `4 * population * mutation_rate` of the neutral part of the density.
+    fn shape(&self) -> f64 {
+        4. * self.population as f64 * self.mutation_rate
+    }
+
+    /// Scaled selection `2 * population * selection`.
+    fn scaled_selection(&self) -> f64 {
+        2. * self.population as f64 * self.selection
+    }
+
+    /// Logarithm of the maximum over [0, 1] of the density without normalization,
+    /// by which it is shifted before integrating, so that integrands neither underflow nor overflow.
+    ///
+    /// If `4 * population * mutation_rate <= 1`, the density is unbounded at the extremes,
+    /// where `singular_integral` absorbs its power part, so only the selection exponent
+    /// `2 * population * selection * (x^2 + 2 * dominance * x * (1 - x))` is maximized.
+    /// It is attained at one of the extremes, since the polynomial is increasing from 0 to 1.
+    fn ln_maximum(&self) -> f64 {
+        let shape = self.shape();
+        let gamma = self.scaled_selection();
+        if shape <= 1. {
+            gamma.max(0.)
+        } else {
+            let (_, left) = half_maximum(shape, gamma, self.dominance, 1., 0.);
+            let (_, right) = half_maximum(shape, -gamma, 1. - self.dominance, 1., 0.);
+            left.max(gamma + right)
         }
     }
+
+    /// Same distribution with `ln_shift` given by `ln_maximum`.
+    fn with_ln_shift(self) -> Self {
+        GeneticFreq {
+            ln_shift: self.ln_maximum(),
+            ..self
+        }
+    }
+
+    /// Logarithm of the density, without normalization and shifted by `ln_shift`,
+    /// divided by `x^(4 * population * mutation_rate - 1)`.
+    ///
+    /// It is smooth close to `x = 0`.
+    fn ln_kernel_left(&self, x: f64) -> f64 {
+        let gamma = self.scaled_selection();
+        let h = self.dominance;
+        gamma * (2. * h * x + (1. - 2. * h) * x * x) - self.ln_shift
+            + (self.shape() - 1.) * (-x).ln_1p()
+    }
+
+    /// Logarithm of the density at `1 - y`, without normalization and shifted by `ln_shift`,
+    /// divided by `y^(4 * population * mutation_rate - 1)`.
+    ///
+    /// It is smooth close to `y = 0`.
+    fn ln_kernel_right(&self, y: f64) -> f64 {
+        let gamma = self.scaled_selection();
+        let h = self.dominance;
+        gamma - self.ln_shift - gamma * (2. * (1. - h) * y - (1. - 2. * h) * y * y)
+            + (self.shape() - 1.) * (-y).ln_1p()
+    }
+
+    /// Returns the expected heterozygosity `E[2 x (1 - x)]`.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// 2 C(4 N U + 1) / C(4 N U)
+    /// ```
+    ///
+    /// where `C(a)` is the normalizing constant of the density with `(x (1 - x))^(a - 1)` as neutral part,
+    /// since multiplying the density by `x (1 - x)` increases the exponent by one.
+    ///
+    /// # Examples
+    ///
+    /// Without selection, it is `a / (2a + 1)`, where `a = 4 N U`.
+    /// ```
+    /// use sandpiper::GeneticFreq;
+    ///
+    /// let gen_freq = GeneticFreq::new(1000, 0.00001, 0.0, 0.5).unwrap();
+    /// let shape = 4. * 1000. * 0.00001;
+    /// let expected = shape / (2. * shape + 1.);
+    /// assert!((gen_freq.expected_heterozygosity() - expected).abs() < 1e-10);
+    /// ```
+    pub fn expected_heterozygosity(&self) -> f64 {
+        2. * (self.increased_shape().ln_normalizing_constant() - self.ln_normalizing_constant())
+            .exp()
+    }
+
+    /// Returns the expected heterozygosity `E[2 x (1 - x)]` conditioned on `lower <= x <= upper`.
+    ///
+    /// # Remarks
+    ///
+    /// The masses of the window are computed on each half of the domain from the closest extreme,
+    /// so that windows that exclude a neighbourhood of an extreme, like `[0, 1 - 1 / (2N)]`, are accurate.
+    ///
+    /// # Examples
+    ///
+    /// Excluding frequencies close to the extremes increases the heterozygosity.
+    /// ```
+    /// use sandpiper::GeneticFreq;
+    ///
+    /// let gen_freq = GeneticFreq::new(1000, 0.00001, -0.001, 0.5).unwrap();
+    /// let lower = 1. / 2000.;
+    /// let upper = 1. - lower;
+    /// assert!(gen_freq.expected_heterozygosity_between(lower, upper) > gen_freq.expected_heterozygosity());
+    /// ```
+    pub fn expected_heterozygosity_between(&self, lower: f64, upper: f64) -> f64 {
+        let increased_shape = self.increased_shape();
+        let (left, right) = self.shifted_masses_between(lower, upper);
+        let (increased_left, increased_right) =
+            increased_shape.shifted_masses_between(lower, upper);
+        2. * (increased_left + increased_right) / (left + right)
+            * (increased_shape.ln_shift - self.ln_shift).exp()
+    }
+
+    /// Samples conditioned on `lower <= x <= upper`.
+    ///
+    /// # Remarks
+    ///
+    /// Proposals of `sample` outside the window are rejected up to `MAX_WINDOW_REJECTIONS` times,
+    /// after which the sample is obtained by inverse transform within the window,
+    /// so that windows with little mass do not stall sampling.
+    /// If the mass of the window underflows, the bound with larger density is returned.
+    ///
+    /// # Examples
+    ///
+    /// Excluding singletons.
+    /// ```
+    /// use sandpiper::GeneticFreq;
+    ///
+    /// let gen_freq = GeneticFreq::new(1000, 0.00001, -0.001, 0.5).unwrap();
+    /// let x = gen_freq.sample_between(1. / 2000., 1., &mut rand::thread_rng());
+    /// assert!(1. / 2000. <= x && x <= 1.);
+    /// ```
+    pub fn sample_between<R: Rng + ?Sized>(&self, lower: f64, upper: f64, rng: &mut R) -> f64 {
+        frequency(self.sample_ln_between(lower, upper, rng))
+    }
+
+    /// Samples the logarithms `(ln x, ln(1 - x))` of the frequency and its complement,
+    /// conditioned on `lower <= x <= upper`.
+    ///
+    /// See `sample_between` and `sample_ln`.
+    ///
+    /// # Examples
+    ///
+    /// Heterozygosity of a sample excluding singletons.
+    /// ```
+    /// use sandpiper::GeneticFreq;
+    ///
+    /// let gen_freq = GeneticFreq::new(1000, 0.00001, -0.001, 0.5).unwrap();
+    /// let (ln_x, ln_y) = gen_freq.sample_ln_between(1. / 2000., 1., &mut rand::thread_rng());
+    /// let heterozygosity = (std::f64::consts::LN_2 + ln_x + ln_y).exp();
+    /// assert!(heterozygosity <= 0.5);
+    /// ```
+    pub fn sample_ln_between<R: Rng + ?Sized>(
+        &self,
+        lower: f64,
+        upper: f64,
+        rng: &mut R,
+    ) -> (f64, f64) {
+        sample_window(
+            rng,
+            |rng| self.sample_ln(rng),
+            |&logarithms| {
+                let x = frequency(logarithms);
+                lower <= x && x <= upper
+            },
+            |uniform| {
+                let (left, right) = self.shifted_masses_between(lower, upper);
+                if left + right <= 0. {
+                    let x = if self.ln_pdf(lower) >= self.ln_pdf(upper) {
+                        lower
+                    } else {
+                        upper
+                    };
+                    return (x.ln(), (-x).ln_1p());
+                }
+                let mass = uniform * (left + right);
+                if mass < left {
+                    let x = singular_integral_inverse(
+                        self.shape(),
+                        |x| self.ln_kernel_left(x),
+                        self.shifted_mass_left(lower) + mass,
+                        upper.min(0.5),
+                    )
+                    .max(lower)
+                    .min(upper);
+                    (x.ln(), (-x).ln_1p())
+                } else {
+                    let y = singular_integral_inverse(
+                        self.shape(),
+                        |y| self.ln_kernel_right(y),
+                        self.shifted_mass_right(1. - upper) + mass - left,
+                        1. - lower.max(0.5),
+                    )
+                    .max(1. - upper)
+                    .min(1. - lower);
+                    ((-y).ln_1p(), y.ln())
+                }
+            },
+        )
+    }
+
+    /// Samples the logarithms `(ln x, ln(1 - x))` of the frequency and its complement.
+    ///
+    /// # Remarks
+    ///
+    /// Both are accurate even when `x` or `1 - x` are too small to be represented,
+    /// which happens often when `4 * population * mutation_rate` is far below one.
+    /// In particular, the heterozygosity `2 x (1 - x)` is best computed as `exp(ln 2 + ln x + ln(1 - x))`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::{GeneticFreq, N_SANDPIPER, U};
+    ///
+    /// let gen_freq = GeneticFreq::new(N_SANDPIPER, U, -0.001, 0.5).unwrap();
+    /// let (ln_x, ln_y) = gen_freq.sample_ln(&mut rand::thread_rng());
+    /// assert!(ln_x.is_finite() && ln_y.is_finite());
+    /// ```
+    pub fn sample_ln<R: Rng + ?Sized>(&self, rng: &mut R) -> (f64, f64) {
+        let (left, right) = self.envelopes();
+        let left_probability = 1. / (1. + (right.ln_mass() - left.ln_mass()).exp());
+        loop {
+            let u: f64 = rng.sample(rand_distr::Open01);
+            if rng.sample::<f64, _>(rand_distr::Standard) < left_probability {
+                let ln_x = left.sample_ln(rng);
+                let x = ln_x.exp();
+                if u.ln() < self.ln_kernel_left(x) - left.ln_bound(x) {
+                    return (ln_x, (-x).ln_1p());
+                }
+            } else {
+                let ln_y = right.sample_ln(rng);
+                let y = ln_y.exp();
+                if u.ln() < self.ln_kernel_right(y) - right.ln_bound(y) {
+                    return ((-y).ln_1p(), ln_y);
+                }
+            }
+        }
+    }
+
+    /// Same distribution with `4 * population * mutation_rate` increased by one,
+    /// whose density is proportional to `x * (1 - x)` times the original one.
+    ///
+    /// Its shifted masses are compared with the original ones after correcting for the difference of `ln_shift`.
+    fn increased_shape(&self) -> Self {
+        GeneticFreq {
+            mutation_rate: self.mutation_rate + 1. / (4. * self.population as f64),
+            ..*self
+        }
+        .with_ln_shift()
+    }
+
+    /// Returns the expected proportion of proposals accepted by `sample`.
+    ///
+    /// # Remarks
+    ///
+    /// The envelope follows the singularities and the selection of the density,
+    /// and its mode and curvature when `4 * population * mutation_rate > 1`,
+    /// so that the acceptance rate is above 0.3 for any parameters.
+    ///
+    /// The normalizing constant is computed by numerical quadrature each time this method is called.
+    ///
+    /// # Examples
+    ///
+    /// Strong selection does not slow down sampling.
+    /// ```
+    /// use sandpiper::{GeneticFreq, N_REDNECK, U};
+    ///
+    /// let gen_freq = GeneticFreq::new(N_REDNECK, U, -0.01, 0.5).unwrap();
+    /// assert!(gen_freq.acceptance_rate() > 0.5);
+    /// ```
+    pub fn acceptance_rate(&self) -> f64 {
+        let (left, right) = self.envelopes();
+        let ln_envelope_mass = left.ln_mass().max(right.ln_mass())
+            + (-(left.ln_mass() - right.ln_mass()).abs()).exp().ln_1p();
+        (self.ln_shifted_normalizing_constant() - ln_envelope_mass).exp()
+    }
+
+    /// Envelopes used by `sample` over `[0, 0.5]` and `[0.5, 1]` respectively,
+    /// in terms of the distance to the closest extreme.
+    fn envelopes(&self) -> (HalfEnvelope, HalfEnvelope) {
+        let shape = self.shape();
+        let gamma = self.scaled_selection();
+        (
+            HalfEnvelope::new(shape, gamma, self.dominance, -self.ln_shift),
+            HalfEnvelope::new(shape, -gamma, 1. - self.dominance, gamma - self.ln_shift),
+        )
+    }
+
+    /// Logarithm of the normalizing constant of the density shifted by `ln_shift`.
+    fn ln_shifted_normalizing_constant(&self) -> f64 {
+        let (left, right) = self.shifted_masses();
+        (left + right).ln()
+    }
+
+    /// Mass of the density, without normalization and shifted by `ln_shift`,
+    /// over `[0, 0.5]` and `[0.5, 1]` respectively.
+    fn shifted_masses(&self) -> (f64, f64) {
+        (self.shifted_mass_left(0.5), self.shifted_mass_right(0.5))
+    }
+
+    /// Mass of the density, without normalization and shifted by `ln_shift`,
+    /// over `[lower, upper]` intersected with `[0, 0.5]` and `[0.5, 1]` respectively.
+    fn shifted_masses_between(&self, lower: f64, upper: f64) -> (f64, f64) {
+        let (lower, upper) = (lower.max(0.), upper.min(1.));
+        let left = if lower < upper.min(0.5) {
+            self.shifted_mass_left(upper.min(0.5)) - self.shifted_mass_left(lower)
+        } else {
+            0.
+        };
+        let right = if lower.max(0.5) < upper {
+            self.shifted_mass_right(1. - lower.max(0.5)) - self.shifted_mass_right(1. - upper)
+        } else {
+            0.
+        };
+        (left.max(0.), right.max(0.))
+    }
+
+    /// Mass of the density, without normalization and shifted by `ln_shift`, over `[0, x]`.
+    fn shifted_mass_left(&self, x: f64) -> f64 {
+        singular_integral(self.shape(), |x| self.ln_kernel_left(x), x)
+    }
+
+    /// Mass of the density, without normalization and shifted by `ln_shift`, over `[1 - y, 1]`.
+    fn shifted_mass_right(&self, y: f64) -> f64 {
+        singular_integral(self.shape(), |y| self.ln_kernel_right(y), y)
+    }
+
+    /// Inverse of the cumulative distribution, given the masses returned by `shifted_masses`.
+    fn inverse_cdf_with(&self, p: f64, (left, right): (f64, f64)) -> f64 {
+        let total = left + right;
+        if p <= 0. {
+            0.
+        } else if p >= 1. {
+            1.
+        } else if p * total <= left {
+            singular_integral_inverse(self.shape(), |x| self.ln_kernel_left(x), p * total, 0.5)
+        } else {
+            let y = singular_integral_inverse(
+                self.shape(),
+                |y| self.ln_kernel_right(y),
+                (1. - p) * total,
+                0.5,
+            );
+            1. - y
+        }
+    }
+
+    /// Samples by inverse transform, that is, evaluating the inverse of the cumulative distribution
+    /// at a uniform random variable.
+    ///
+    /// # Remarks
+    ///
+    /// Each sample requires several numerical quadratures, so this is much slower than `sample`
+    /// unless selection is strong. For many samples of the same distribution, prefer `sample_iter_inverse_transform`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::GeneticFreq;
+    ///
+    /// let gen_freq = GeneticFreq::new(1000, 0.00001, -0.001, 0.5).unwrap();
+    /// let x = gen_freq.sample_inverse_transform(&mut rand::thread_rng());
+    /// assert!(0. <= x && x <= 1.);
+    /// ```
+    pub fn sample_inverse_transform<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let p: f64 = rng.sample(rand_distr::Standard);
+        self.inverse_cdf(p)
+    }
+
+    /// Returns an iterator of samples obtained by inverse transform,
+    /// computing the normalizing constant only once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::GeneticFreq;
+    ///
+    /// let gen_freq = GeneticFreq::new(1000, 0.00001, -0.001, 0.5).unwrap();
+    /// let samples: Vec<f64> = gen_freq
+    ///     .sample_iter_inverse_transform(rand::thread_rng())
+    ///     .take(10)
+    ///     .collect();
+    /// assert_eq!(samples.len(), 10);
+    /// ```
+    pub fn sample_iter_inverse_transform<R: Rng>(self, mut rng: R) -> impl Iterator<Item = f64> {
+        let masses = self.shifted_masses();
+        std::iter::repeat_with(move || {
+            let p: f64 = rng.sample(rand_distr::Standard);
+            self.inverse_cdf_with(p, masses)
+        })
+    }
+
+    /// Logarithm of the density, given the logarithm of the shifted normalizing constant.
+    fn ln_pdf_with(&self, x: f64, ln_shifted_normalizing_constant: f64) -> f64 {
+        if !(0.0..=1.0).contains(&x) {
+            f64::NEG_INFINITY
+        } else if x <= 0.5 {
+            (self.shape() - 1.) * x.ln() + self.ln_kernel_left(x) - ln_shifted_normalizing_constant
+        } else {
+            let y = 1. - x;
+            (self.shape() - 1.) * y.ln() + self.ln_kernel_right(y) - ln_shifted_normalizing_constant
+        }
+    }
+}
+
+/// Computes `\int_0^upper t^(shape - 1) * \exp(ln_kernel(t)) dt` for a smooth `ln_kernel`.
+///
+/// # Algorithm
+///
+/// If `shape < 1`, the change of variables `t = u^(1 / shape)` removes the (integrable) singularity at zero,
+/// leading to `1 / shape * \int_0^(upper^shape) \exp(ln_kernel(u^(1 / shape))) du`,
+/// whose integrand is bounded. Otherwise, the integrand is already bounded and it is integrated directly,
+/// in both cases by `relative_integral`.
+fn singular_integral<F>(shape: f64, ln_kernel: F, upper: f64) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    if upper <= 0. {
+        return 0.;
+    }
+    relative_integral(
+        |w| coordinate_density(shape, &ln_kernel, w),
+        &[0., to_coordinate(shape, upper)],
+    )
+    .integral
+}
+
+/// Finds `upper` in `[0, maximum]` such that `singular_integral(shape, ln_kernel, upper) == mass`.
+///
+/// # Algorithm
+///
+/// Newton's method in the coordinate where the integrand is bounded,
+/// safeguarded by bisection with `safeguarded_newton`.
+fn singular_integral_inverse<F>(shape: f64, ln_kernel: F, mass: f64, maximum: f64) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let density = |w: f64| coordinate_density(shape, &ln_kernel, w);
+    let upper = to_coordinate(shape, maximum);
+    let total = relative_integral(density, &[0., upper]).integral;
+    if mass <= 0. {
+        return 0.;
+    }
+    if mass >= total {
+        return maximum;
+    }
+    let value_and_derivative = |w: f64| {
+        let excess = relative_integral(density, &[0., w]).integral - mass;
+        (excess, density(w))
+    };
+    let w = safeguarded_newton(value_and_derivative, (0., upper), upper * mass / total);
+    from_coordinate(shape, w)
+}
+
+/// Maximum over `(0, 0.5]` of
+///
+/// `gamma * (2 * dominance * t + (1 - 2 * dominance) * t^2) + (shape - 1) * ln(1 - t) + (shape - exponent) * ln(t) - slope * t`
+///
+/// and the point where it is attained, for `shape > 1` and `exponent <= shape`.
+/// With `exponent = 1` and `slope = 0`, it is the maximum of the logarithm of the density on one half of the domain.
+///
+/// # Algorithm
+///
+/// The derivative multiplied by `t * (1 - t)` is a cubic polynomial,
+/// so the maximum is attained at one of its roots or at the extremes.
+fn half_maximum(shape: f64, gamma: f64, dominance: f64, exponent: f64, slope: f64) -> (f64, f64) {
+    let upper = 0.5;
+    let (a, b) = (2. * dominance, 2. * (1. - 2. * dominance));
+    let coefficients = [
+        shape - exponent,
+        gamma * a - (shape - exponent) - (shape - 1.) - slope,
+        gamma * (b - a) + slope,
+        -gamma * b,
+    ];
+    let value = |t: f64| {
+        let mut value = gamma * (a * t + 0.5 * b * t * t) + (shape - 1.) * (-t).ln_1p() - slope * t;
+        if exponent != shape {
+            value += (shape - exponent) * t.ln();
+        }
+        value
+    };
+    cubic_roots(coefficients, upper)
+        .into_iter()
+        .chain(vec![0., upper])
+        .map(|t| (t, value(t)))
+        .fold((upper, f64::NEG_INFINITY), |maximum, candidate| {
+            if candidate.1 > maximum.1 {
+                candidate
+            } else {
+                maximum
+            }
+        })
+}
+
+/// Roots in `(0, upper)` where the cubic polynomial with the given coefficients, in increasing degree, changes sign.
+///
+/// # Algorithm
+///
+/// The roots of the derivative split the interval into pieces where the polynomial is monotone,
+/// and each piece whose extremes have different signs is bisected.
+fn cubic_roots(coefficients: [f64; 4], upper: f64) -> Vec<f64> {
+    let [c0, c1, c2, c3] = coefficients;
+    let value = |t: f64| c0 + t * (c1 + t * (c2 + t * c3));
+
+    // Roots of the derivative `3 c3 t^2 + 2 c2 t + c1`
+    let mut points = vec![0., upper];
+    if c3 != 0. {
+        let discriminant = c2 * c2 - 3. * c3 * c1;
+        if discriminant >= 0. {
+            let q = -(c2 + c2.signum() * discriminant.sqrt());
+            points.push(q / (3. * c3));
+            if q != 0. {
+                points.push(c1 / q);
+            }
+        }
+    } else if c2 != 0. {
+        points.push(-c1 / (2. * c2));
+    }
+    points.retain(|&t| 0. <= t && t <= upper);
+    points.sort_by(|t, s| t.partial_cmp(s).unwrap());
+
+    points
+        .windows(2)
+        .filter(|piece| value(piece[0]) * value(piece[1]) < 0.)
+        .map(|piece| {
+            let (mut lower, mut upper) = (piece[0], piece[1]);
+            let lower_positive = value(lower) > 0.;
+            for _ in 0..MAX_ROOT_ITERATIONS {
+                let middle = 0.5 * (lower + upper);
+                if middle <= lower || middle >= upper {
+                    break;
+                }
+                if (value(middle) > 0.) == lower_positive {
+                    lower = middle;
+                } else {
+                    upper = middle;
+                }
+            }
+            0.5 * (lower + upper)
+        })
+        .collect()
+}
+
+/// Maximum number of bisection steps in `cubic_roots`.
+const MAX_ROOT_ITERATIONS: usize = 200;
+
+/// Coordinate in which `t^(shape - 1)` is absorbed, so that the integrand of `singular_integral` is bounded.
+fn to_coordinate(shape: f64, t: f64) -> f64 {
+    if shape < 1. {
+        t.powf(shape)
+    } else {
+        t
+    }
+}
+
+/// Inverse of `to_coordinate`.
+fn from_coordinate(shape: f64, w: f64) -> f64 {
+    if shape < 1. {
+        w.powf(shape.recip())
+    } else {
+        w
+    }
+}
+
+/// Integrand `t^(shape - 1) * \exp(ln_kernel(t))` written in the coordinate given by `to_coordinate`.
+fn coordinate_density<F>(shape: f64, ln_kernel: F, w: f64) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    if shape < 1. {
+        ln_kernel(from_coordinate(shape, w)).exp() / shape
+    } else {
+        ((shape - 1.) * w.ln() + ln_kernel(w)).exp()
+    }
+}
+
+/// Frequency `x` from the logarithms `(ln x, ln(1 - x))`, computed from the smallest of `x` and `1 - x`.
+fn frequency((ln_x, ln_y): (f64, f64)) -> f64 {
+    if ln_x <= ln_y {
+        ln_x.exp()
+    } else {
+        -ln_y.exp_m1()
+    }
 }
 
 impl Distribution<f64> for GeneticFreq {
+    /// Samples by rejection from an envelope adapted to selection on each half of the domain.
+    ///
+    /// See `acceptance_rate` for the expected proportion of accepted proposals,
+    /// and `sample_ln` for frequencies too close to the extremes to be represented.
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
-        let shape = 4. * self.population as f64 * self.mutation_rate;
-        let beta: Beta<f64> = Beta::new(shape, shape).unwrap();
-        let mut proposal: f64 = beta.sample(rng);
-
-        if self.selection < 0. {
-            let reshaping = |x: f64| {
-                (2. * self.population as f64
-                    * self.selection
-                    * (x.powi(2) + 2. * self.dominance * x * (1. - x)))
-                    .exp()
-            };
-            while rng.sample::<f64, _>(rand_distr::Standard) > reshaping(proposal) {
-                proposal = beta.sample(rng);
+        frequency(self.sample_ln(rng))
+    }
+}
+
+/// Upper bound `t^(exponent - 1) * exp(intercept + slope * t)` over `[0, 0.5]`
+/// of the (shifted) density on one half of the domain,
+/// where `t` is the distance to the closest extreme.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct HalfEnvelope {
+    shape: f64,
+    exponent: f64,
+    intercept: f64,
+    slope: f64,
+}
+
+impl HalfEnvelope {
+    /// Constructs the envelope of `t^(shape - 1) * exp(offset + gamma * (2 h t + (1 - 2 h) t^2)) * (1 - t)^(shape - 1)`.
+    ///
+    /// # Algorithm
+    ///
+    /// If `shape > 1` and the density has a mode inside `(0, 0.5)`,
+    /// the envelope with least mass among `around_mode` and `tangent` is chosen.
+    /// Otherwise, the envelope is given by `tangent`.
+    fn new(shape: f64, gamma: f64, dominance: f64, offset: f64) -> Self {
+        let tangent = HalfEnvelope::tangent(shape, gamma, dominance, offset);
+        if shape <= 1. {
+            return tangent;
+        }
+        HalfEnvelope::around_mode(shape, gamma, dominance, offset)
+            .into_iter()
+            .fold(tangent, |best, envelope| {
+                if envelope.ln_mass() < best.ln_mass() {
+                    envelope
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// Candidate envelopes given by Gamma densities with the same mode as the density, for `shape > 1`,
+    /// or none if the mode is not inside `(0, 0.5)`.
+    ///
+    /// # Algorithm
+    ///
+    /// The first candidate has the same curvature of the logarithm at the mode, as in Laplace's method.
+    /// Its rate is decreased if needed, so that `exponent` is at most `shape` and the envelope dominates close to zero,
+    /// and so that the envelope does not decrease more than the density from the mode to `0.5`.
+    /// The others have smaller exponents, hence wider tails, in case the first one is too narrow away from the mode.
+    /// The intercept of each one is the maximum of the logarithm of the ratio, found by `half_maximum`.
+    fn around_mode(shape: f64, gamma: f64, dominance: f64, offset: f64) -> Vec<Self> {
+        let upper = 0.5;
+        let (mode, ln_maximum) = half_maximum(shape, gamma, dominance, 1., 0.);
+        let curvature = (shape - 1.) * (mode.powi(-2) + (1. - mode).powi(-2))
+            - 2. * gamma * (1. - 2. * dominance);
+        if !(0. < mode && mode < upper && curvature > 0.) {
+            return Vec::new();
+        }
+        let ln_upper_density = gamma
+            * (2. * dominance * upper + (1. - 2. * dominance) * upper * upper)
+            + (shape - 1.) * (upper.ln() + (-upper).ln_1p());
+        // The logarithm of `t^(rate * mode) * exp(-rate * t)` decreases by `rate` times this from the mode to `upper`
+        let envelope_decrease = upper - mode - mode * (upper / mode).ln();
+        let rate = (curvature * mode)
+            .min((shape - 1.) / mode)
+            .min((ln_maximum - ln_upper_density) / envelope_decrease);
+
+        MODE_RATE_FACTORS
+            .iter()
+            .map(|factor| {
+                let rate = rate * factor;
+                let exponent = (1. + rate * mode).min(shape);
+                let slope = -rate;
+                let (_, intercept) = half_maximum(shape, gamma, dominance, exponent, slope);
+                HalfEnvelope {
+                    shape,
+                    exponent,
+                    intercept: offset + intercept,
+                    slope,
+                }
+            })
+            .collect()
+    }
+
+    /// Envelope keeping the power part `t^(shape - 1)`, so that the singularity at zero is handled exactly.
+    ///
+    /// # Algorithm
+    ///
+    /// The rest of the logarithm of the density is bounded by a linear function:
+    /// concave terms by their tangent and convex terms by their chord.
+    /// The tangent point is chosen to minimize the mass of the envelope, that is,
+    /// as the mean of the resulting (truncated) Gamma distribution.
+    /// If the resulting rate is too small, the exponential factor is bounded by a constant.
+    fn tangent(shape: f64, gamma: f64, dominance: f64, offset: f64) -> Self {
+        let upper = 0.5;
+        let exponent = shape;
+        let curvature = gamma * (1. - 2. * dominance);
+
+        // Convex terms
+        let mut chord_slope = 0.;
+        if curvature > 0. {
+            chord_slope += gamma * (2. * dominance + (1. - 2. * dominance) * upper);
+        }
+        if shape < 1. {
+            chord_slope += (shape - 1.) * (-upper).ln_1p() / upper;
+        }
+
+        // Concave terms
+        let concave_value = |x: f64| -> f64 {
+            let mut value = 0.;
+            if curvature <= 0. {
+                value += gamma * (2. * dominance * x + (1. - 2. * dominance) * x * x);
+            }
+            if shape >= 1. {
+                value += (shape - 1.) * (-x).ln_1p();
+            }
+            value
+        };
+        let concave_derivative = |x: f64| -> f64 {
+            let mut derivative = 0.;
+            if curvature <= 0. {
+                derivative += gamma * (2. * dominance + 2. * (1. - 2. * dominance) * x);
+            }
+            if shape >= 1. {
+                derivative -= (shape - 1.) / (1. - x);
+            }
+            derivative
+        };
+
+        // Tangent point: `x (-slope(x)) = exponent`, where the left-hand side is increasing
+        let excess = |x: f64| -> f64 { -x * (concave_derivative(x) + chord_slope) - exponent };
+        let tangent_point = if excess(upper) <= 0. {
+            upper
+        } else {
+            let (mut lower_point, mut upper_point) = (0., upper);
+            for _ in 0..TANGENT_POINT_ITERATIONS {
+                let middle = (lower_point + upper_point) * 0.5;
+                if excess(middle) > 0. {
+                    upper_point = middle;
+                } else {
+                    lower_point = middle;
+                }
+            }
+            upper_point
+        };
+
+        let slope = concave_derivative(tangent_point) + chord_slope;
+        let intercept = offset + concave_value(tangent_point)
+            - concave_derivative(tangent_point) * tangent_point;
+        if slope < 0. && -slope * upper >= 1. {
+            HalfEnvelope {
+                shape,
+                exponent,
+                intercept,
+                slope,
             }
         } else {
-            let reshaping = |x: f64| {
-                (2. * self.population as f64
-                    * self.selection
-                    * (x.powi(2) + 2. * self.dominance * x * (1. - x) - 1.))
-                    .exp()
-            };
-            while rng.sample::<f64, _>(rand_distr::Standard) > reshaping(proposal) {
-                proposal = beta.sample(rng);
+            HalfEnvelope {
+                shape,
+                exponent,
+                intercept: intercept + slope.max(0.) * upper,
+                slope: 0.,
             }
         }
+    }
+
+    /// Logarithm of the envelope at `t` divided by `t^(shape - 1)`.
+    fn ln_bound(&self, t: f64) -> f64 {
+        let mut bound = self.intercept + self.slope * t;
+        if self.exponent != self.shape {
+            bound += (self.exponent - self.shape) * t.ln();
+        }
+        bound
+    }
+
+    /// Logarithm of the mass of the envelope over `[0, 0.5]`.
+    fn ln_mass(&self) -> f64 {
+        let upper: f64 = 0.5;
+        if self.slope < 0. {
+            let rate = -self.slope;
+            self.intercept + statrs::function::gamma::ln_gamma(self.exponent)
+                - self.exponent * rate.ln()
+                + statrs::function::gamma::gamma_lr(self.exponent, rate * upper).ln()
+        } else {
+            self.intercept + self.exponent * upper.ln() - self.exponent.ln()
+        }
+    }
 
-        proposal
+    /// Samples the logarithm of a variable following the normalized envelope over `[0, 0.5]`.
+    fn sample_ln<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let ln_upper = 0.5_f64.ln();
+        if self.slope < 0. {
+            let ln_rate = (-self.slope).ln();
+            loop {
+                let proposal = sample_ln_gamma(self.exponent, rng) - ln_rate;
+                if proposal < ln_upper {
+                    return proposal;
+                }
+            }
+        } else {
+            let u: f64 = rng.sample(rand_distr::Open01);
+            ln_upper + u.ln() / self.exponent
+        }
     }
 }
 
+/// Factors of the rate of the candidates of `HalfEnvelope::around_mode`.
+const MODE_RATE_FACTORS: [f64; 6] = [1., 0.999, 0.996, 0.984, 0.9375, 0.75];
+
+/// Number of bisection steps when searching the tangent point of `HalfEnvelope`.
+const TANGENT_POINT_ITERATIONS: usize = 60;
+
 impl Min<f64> for GeneticFreq {
     /// Returns the minimum value in the domain of the
-    /// skew normal distribution representable by a double precision float
+    /// genetic frequency distribution
     ///
     /// # Formula
     ///
     /// ```ignore
-    /// -INF
+    /// 0
     /// ```
     fn min(&self) -> f64 {
-        f64::NEG_INFINITY
+        0.0
     }
 }
 
 impl Max<f64> for GeneticFreq {
     /// Returns the maximum value in the domain of the
-    /// skew normal distribution representable by a double precision float
+    /// genetic frequency distribution
     ///
     /// # Formula
     ///
     /// ```ignore
-    /// INF
+    /// 1
     /// ```
     fn max(&self) -> f64 {
-        f64::INFINITY
+        1.0
+    }
+}
+
+impl Univariate<f64, f64> for GeneticFreq {
+    /// Calculates the cumulative distribution function for the
+    /// genetic frequency distribution at `x`.
+    ///
+    /// # Remarks
+    ///
+    /// It is computed by numerical quadrature from the closest extreme,
+    /// so that both tails are accurate in relative terms.
+    ///
+    /// # Examples
+    ///
+    /// Fraction of sites with frequency below 0.05.
+    /// ```
+    /// use sandpiper::GeneticFreq;
+    /// use statrs::distribution::Univariate;
+    ///
+    /// let gen_freq = GeneticFreq::new(1000, 0.00001, -0.001, 0.5).unwrap();
+    /// let fraction = gen_freq.cdf(0.05);
+    /// assert!(0.5 < fraction && fraction < 1.);
+    /// ```
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= 0. {
+            0.
+        } else if x >= 1. {
+            1.
+        } else {
+            let (left, right) = self.shifted_masses();
+            if x <= 0.5 {
+                self.shifted_mass_left(x) / (left + right)
+            } else {
+                1. - self.shifted_mass_right(1. - x) / (left + right)
+            }
+        }
+    }
+}
+
+impl InverseCDF<f64> for GeneticFreq {
+    /// Calculates the inverse cumulative distribution function for the
+    /// genetic frequency distribution at `x`.
+    ///
+    /// # Panics
+    ///
+    /// If `x < 0.0` or `x > 1.0`
+    ///
+    /// # Remarks
+    ///
+    /// It is computed by Newton's method over the numerical quadrature of the density,
+    /// in a change of variables that handles the singularities at the extremes.
+    fn inverse_cdf(&self, x: f64) -> f64 {
+        self.checked_inverse_cdf(x).unwrap()
+    }
+}
+
+impl CheckedInverseCDF<f64> for GeneticFreq {
+    /// Calculates the inverse cumulative distribution function for the
+    /// genetic frequency distribution at `x`.
+    ///
+    /// # Errors
+    ///
+    /// If `x < 0.0` or `x > 1.0`
+    fn checked_inverse_cdf(&self, x: f64) -> Result<f64> {
+        if !(0.0..=1.0).contains(&x) {
+            Err(StatsError::ArgIntervalIncl("x", 0.0, 1.0))
+        } else {
+            Ok(self.inverse_cdf_with(x, self.shifted_masses()))
+        }
+    }
+}
+
+impl Continuous<f64, f64> for GeneticFreq {
+    /// Calculates the probability density function for the
+    /// genetic frequency distribution at `x`.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// exp(2 N s (x^2 + 2 h x (1 - x))) * (x (1 - x))^(4 N U - 1) / C
+    /// ```
+    ///
+    /// where `N` is the population, `U` the mutation rate, `s` the selection,
+    /// `h` the dominance and `C` the normalizing constant.
+    ///
+    /// # Remarks
+    ///
+    /// The normalizing constant is computed by numerical quadrature at each call.
+    fn pdf(&self, x: f64) -> f64 {
+        self.ln_pdf(x).exp()
+    }
+
+    /// Calculates the log probability density function for the
+    /// genetic frequency distribution at `x`.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// 2 N s (x^2 + 2 h x (1 - x)) + (4 N U - 1) ln(x (1 - x)) - ln(C)
+    /// ```
+    ///
+    /// where `N` is the population, `U` the mutation rate, `s` the selection,
+    /// `h` the dominance and `C` the normalizing constant.
+    fn ln_pdf(&self, x: f64) -> f64 {
+        self.ln_pdf_with(x, self.ln_shifted_normalizing_constant())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::constants::{N_REDNECK, N_SANDPIPER, U};
+    use quadrature::integrate;
+    use test_case::test_case;
 
     #[test]
     fn mean() {
@@ -157,4 +1072,337 @@ mod tests {
 
         assert!((expected - sampled).abs() < 1e-2);
     }
+
+    /// Kummer's confluent hypergeometric function `1F1(a; b; z)` by its power series.
+    fn hyp1f1(a: f64, b: f64, z: f64) -> f64 {
+        let mut term = 1.;
+        let mut sum = 1.;
+        for n in 0..1000 {
+            let n = n as f64;
+            term *= (a + n) / (b + n) * z / (n + 1.);
+            sum += term;
+        }
+        sum
+    }
+
+    #[test_case(0.0; "neutral")]
+    #[test_case(-5.0; "negative")]
+    #[test_case(5.0; "positive")]
+    #[test_case(-20.0; "large negative")]
+    #[test_case(20.0; "large positive")]
+    fn normalizing_constant_additive(scaled_selection: f64) {
+        // With dominance 0.5, the constant is `B(a, a) 1F1(a; 2a; 2Ns)`
+        for &population in [100, 1000, 10000].iter() {
+            let mutation_rate = 0.0001;
+            let selection = scaled_selection / (2. * population as f64);
+            let gen_freq = GeneticFreq::new(population, mutation_rate, selection, 0.5).unwrap();
+            let shape = 4. * population as f64 * mutation_rate;
+            let expected = statrs::function::beta::ln_beta(shape, shape)
+                + hyp1f1(shape, 2. * shape, scaled_selection).ln();
+
+            let result = gen_freq.ln_normalizing_constant();
+            println!("computed value: {:?}, expected: {:?}", result, expected);
+            assert!((result - expected).abs() < 1e-8);
+        }
+    }
+
+    #[test_case(10., 0.0; "neutral")]
+    #[test_case(200., 0.0; "neutral large")]
+    #[test_case(1000., 0.0; "neutral very large")]
+    #[test_case(200., -20.0; "negative")]
+    #[test_case(1000., 20.0; "positive")]
+    fn normalizing_constant_large_shape(shape: f64, scaled_selection: f64) {
+        // The power part alone underflows at `x = 0.5` when the shape is large
+        let population = 1000;
+        let mutation_rate = shape / (4. * population as f64);
+        let selection = scaled_selection / (2. * population as f64);
+        let gen_freq = GeneticFreq::new(population, mutation_rate, selection, 0.5).unwrap();
+        // Kummer's transformation `1F1(a; 2a; z) = e^z 1F1(a; 2a; -z)` avoids cancellation in the series
+        let expected = statrs::function::beta::ln_beta(shape, shape)
+            + scaled_selection.min(0.)
+            + hyp1f1(shape, 2. * shape, scaled_selection.abs()).ln();
+
+        let result = gen_freq.ln_normalizing_constant();
+        println!("computed value: {:?}, expected: {:?}", result, expected);
+        assert!((result - expected).abs() < 1e-12 * expected.abs());
+        assert!(gen_freq.pdf(0.5).is_finite() && gen_freq.pdf(0.5) > 0.);
+        let expected = shape / (2. * shape + 1.);
+        if scaled_selection == 0. {
+            assert!((gen_freq.expected_heterozygosity() - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test_case(N_SANDPIPER, -1.0, 0.1; "sandpiper lethal")]
+    #[test_case(N_SANDPIPER, 1.0, 0.9; "sandpiper beneficial")]
+    #[test_case(N_REDNECK, -0.01, 0.5; "redneck deleterious")]
+    #[test_case(N_REDNECK, 0.01, 0.5; "redneck beneficial")]
+    #[test_case(N_REDNECK, -1.0, 0.2; "redneck lethal")]
+    fn normalizing_constant_strong_selection(population: u64, selection: f64, dominance: f64) {
+        // With strong selection, the density concentrates around one extreme
+        // and the constant is approximated by `Gamma(a) / (2 N |s| c)^a`
+        // where `c` is the slope of the selection exponent at that extreme.
+        let gen_freq = GeneticFreq::new(population, U, selection, dominance).unwrap();
+        let shape = 4. * population as f64 * U;
+        let slope = if selection < 0. {
+            2. * dominance
+        } else {
+            2. * (1. - dominance)
+        };
+        let rate = 2. * population as f64 * selection.abs() * slope;
+        let expected = 2. * population as f64 * selection.max(0.)
+            + statrs::function::gamma::ln_gamma(shape)
+            - shape * rate.ln();
+
+        let result = gen_freq.ln_normalizing_constant();
+        println!("computed value: {:?}, expected: {:?}", result, expected);
+        assert!(result.is_finite());
+        assert!((result - expected).abs() < 1e-3);
+    }
+
+    #[test_case(10000, 0.001, 0.0, 0.5; "neutral")]
+    #[test_case(10000, 0.001, -0.001, 0.5; "negative")]
+    #[test_case(10000, 0.001, 0.001, 0.1; "positive")]
+    #[test_case(1000, 0.0005, -0.01, 0.3; "flat")]
+    #[test_case(1000, 0.05, -1., 0.9; "large shape")]
+    fn pdf_integrates_to_one(population: u64, mutation_rate: f64, selection: f64, dominance: f64) {
+        let gen_freq = GeneticFreq::new(population, mutation_rate, selection, dominance).unwrap();
+
+        let result = integrate(|x| gen_freq.pdf(x), 0., 1., 1e-12).integral;
+        println!("computed value: {:?}", result);
+        assert!((result - 1.).abs() < 1e-8);
+    }
+
+    #[test_case(0.04; "small shape")]
+    #[test_case(0.5; "medium shape")]
+    #[test_case(4.0; "large shape")]
+    fn cdf_neutral(shape: f64) {
+        let population = 1000;
+        let gen_freq = GeneticFreq::new(population, shape / 4000., 0.0, 0.5).unwrap();
+        for &x in [1e-10, 0.001, 0.05, 0.3, 0.5, 0.8, 0.999].iter() {
+            let expected = statrs::function::beta::beta_reg(shape, shape, x);
+            let result = gen_freq.cdf(x);
+            println!(
+                "x: {}, computed value: {:?}, expected: {:?}",
+                x, result, expected
+            );
+            assert!((result - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test_case(1000, 0.00001, -0.001, 0.5; "negative")]
+    #[test_case(1000, 0.00001, 0.001, 0.5; "positive")]
+    #[test_case(N_SANDPIPER, U, -0.001, 0.2; "sandpiper")]
+    #[test_case(N_REDNECK, U, -0.01, 0.5; "redneck")]
+    #[test_case(N_REDNECK, U, 0.01, 0.5; "redneck beneficial")]
+    #[test_case(10000, 0.001, -0.0001, 0.5; "large shape")]
+    fn inverse_mass_roundtrip(population: u64, mutation_rate: f64, selection: f64, dominance: f64) {
+        // Quantiles close to 1 are not representable as `x`, so each half is checked in terms of
+        // the distance to its extreme, as long as it does not underflow.
+        let gen_freq = GeneticFreq::new(population, mutation_rate, selection, dominance).unwrap();
+        let shape = gen_freq.shape();
+        let kernels: [&dyn Fn(f64) -> f64; 2] = [&|x| gen_freq.ln_kernel_left(x), &|y| {
+            gen_freq.ln_kernel_right(y)
+        }];
+        for ln_kernel in kernels.iter() {
+            let total = singular_integral(shape, ln_kernel, 0.5);
+            for &fraction in [1e-12, 0.01, 0.25, 0.5, 0.75, 0.99, 1. - 1e-12].iter() {
+                let mass = fraction * total;
+                let t = singular_integral_inverse(shape, ln_kernel, mass, 0.5);
+                let result = singular_integral(shape, ln_kernel, t);
+                println!("mass: {}, t: {}, computed value: {:?}", mass, t, result);
+                assert!((0.0..=0.5).contains(&t));
+                if t > 0. {
+                    assert!((result - mass).abs() < 1e-9 * mass);
+                }
+            }
+        }
+    }
+
+    #[test_case(1000, 0.00001, -0.001, 0.5; "negative")]
+    #[test_case(10000, 0.001, -0.0001, 0.5; "large shape")]
+    #[test_case(10000, 0.001, 0.0001, 0.2; "large shape positive")]
+    fn inverse_cdf_roundtrip(population: u64, mutation_rate: f64, selection: f64, dominance: f64) {
+        let gen_freq = GeneticFreq::new(population, mutation_rate, selection, dominance).unwrap();
+        for &p in [0.01, 0.25, 0.5, 0.75].iter() {
+            let x = gen_freq.inverse_cdf(p);
+            let result = gen_freq.cdf(x);
+            println!("p: {}, x: {}, computed value: {:?}", p, x, result);
+            assert!((result - p).abs() < 1e-9);
+        }
+        assert_eq!(gen_freq.inverse_cdf(0.), 0.);
+        assert_eq!(gen_freq.inverse_cdf(1.), 1.);
+        assert!(gen_freq.checked_inverse_cdf(1.5).is_err());
+    }
+
+    #[test]
+    fn inverse_transform_mean() {
+        let gen_freq = GeneticFreq::new(1000, 0.0001, -0.001, 0.3).unwrap();
+        let samples = 10_000;
+
+        let expected = gen_freq
+            .sample_iter(crate::tests::rng(1))
+            .take(samples * 10)
+            .sum::<f64>()
+            / (samples * 10) as f64;
+        let result = gen_freq
+            .sample_iter_inverse_transform(crate::tests::rng(2))
+            .take(samples)
+            .sum::<f64>()
+            / samples as f64;
+
+        println!("computed value: {:?}, expected: {:?}", result, expected);
+        assert!((expected - result).abs() < 1e-2);
+    }
+
+    #[test_case(N_SANDPIPER, U; "sandpiper")]
+    #[test_case(N_REDNECK, U; "redneck")]
+    #[test_case(1000, 0.0001; "medium shape")]
+    #[test_case(1000, 0.001; "large shape")]
+    #[test_case(1000, 0.0025; "shape 10")]
+    #[test_case(1000, 0.01; "shape 40")]
+    #[test_case(1000, 0.05; "shape 200")]
+    #[test_case(1000, 0.25; "shape 1000")]
+    fn acceptance_rate_bounded(population: u64, mutation_rate: f64) {
+        for &selection in [-1., -0.01, -0.0001, -1e-6, 0., 1e-6, 0.0001, 0.01, 1.].iter() {
+            for &dominance in [0., 0.2, 0.5, 0.8, 1.].iter() {
+                let gen_freq =
+                    GeneticFreq::new(population, mutation_rate, selection, dominance).unwrap();
+                let result = gen_freq.acceptance_rate();
+                println!(
+                    "selection: {}, dominance: {}, computed value: {:?}",
+                    selection, dominance, result
+                );
+                assert!(result > 0.3);
+                assert!(result < 1. + 1e-6);
+            }
+        }
+    }
+
+    #[test_case(N_REDNECK, U, -0.01, 0.5; "redneck deleterious")]
+    #[test_case(N_REDNECK, U, 0.01, 0.2; "redneck beneficial")]
+    #[test_case(N_SANDPIPER, U, -1e-6, 0.5; "sandpiper nearly neutral")]
+    #[test_case(1000, 0.0001, -0.01, 0.9; "dominant deleterious")]
+    #[test_case(1000, 0.001, 0.001, 0.5; "large shape")]
+    #[test_case(1000, 0.01, -0.1, 0.9; "shape 40 dominant deleterious")]
+    #[test_case(1000, 0.25, -0.5, 1.; "shape 1000 dominant deleterious")]
+    fn sample_quantiles(population: u64, mutation_rate: f64, selection: f64, dominance: f64) {
+        let gen_freq = GeneticFreq::new(population, mutation_rate, selection, dominance).unwrap();
+        let samples = 100_000;
+        let realizations: Vec<f64> = gen_freq
+            .sample_iter(crate::tests::rng(1))
+            .take(samples)
+            .collect();
+        let fraction_below = |x: f64| -> f64 {
+            realizations.iter().filter(|&&sample| sample <= x).count() as f64 / samples as f64
+        };
+        // Quantiles rounded to an extreme are not checked
+        for &p in [0.1, 0.5, 0.9].iter() {
+            let quantile = gen_freq.inverse_cdf(p);
+            if 0. < quantile && quantile < 1. {
+                let result = fraction_below(quantile);
+                println!("p: {}, computed value: {:?}", p, result);
+                assert!((result - p).abs() < 1e-2);
+            }
+        }
+        let expected = gen_freq.cdf(0.5);
+        let result = fraction_below(0.5);
+        println!("computed value: {:?}, expected: {:?}", result, expected);
+        assert!((result - expected).abs() < 1e-2);
+    }
+
+    #[test_case(N_REDNECK, U, -0.01, 0.5; "redneck deleterious")]
+    #[test_case(N_SANDPIPER, U, 0.0001, 0.2; "sandpiper beneficial")]
+    #[test_case(1000, 0.0001, -0.001, 0.9; "medium shape")]
+    fn expected_heterozygosity(
+        population: u64,
+        mutation_rate: f64,
+        selection: f64,
+        dominance: f64,
+    ) {
+        let gen_freq = GeneticFreq::new(population, mutation_rate, selection, dominance).unwrap();
+        let samples = 1_000_000;
+        let expected = gen_freq
+            .sample_iter(crate::tests::rng(1))
+            .take(samples)
+            .map(|x| 2. * x * (1. - x))
+            .sum::<f64>()
+            / samples as f64;
+
+        let result = gen_freq.expected_heterozygosity();
+        println!("computed value: {:?}, expected: {:?}", result, expected);
+        assert!((result - expected).abs() < 1e-2 * expected);
+    }
+
+    #[test_case(N_SANDPIPER, U, 0.001, 0.5, true; "sandpiper beneficial")]
+    #[test_case(N_SANDPIPER, U, -0.001, 0.5, false; "sandpiper deleterious")]
+    fn sample_ln(
+        population: u64,
+        mutation_rate: f64,
+        selection: f64,
+        dominance: f64,
+        close_to_fixation: bool,
+    ) {
+        let gen_freq = GeneticFreq::new(population, mutation_rate, selection, dominance).unwrap();
+        let mut rng = crate::tests::rng(6);
+        let samples = 1_000_000;
+        let realizations: Vec<(f64, f64)> =
+            (0..samples).map(|_| gen_freq.sample_ln(&mut rng)).collect();
+        // Frequencies rounded to 1 that still have a representable heterozygosity
+        let rounded = realizations
+            .iter()
+            .filter(|&&logarithms| {
+                frequency(logarithms) == 1. && (logarithms.0 + logarithms.1).exp() > 0.
+            })
+            .count();
+        println!("rounded samples: {:?}", rounded);
+        assert_eq!(rounded > 0, close_to_fixation);
+
+        let mc: average::Variance = realizations
+            .iter()
+            .map(|(ln_x, ln_y)| (f64::consts::LN_2 + ln_x + ln_y).exp())
+            .collect();
+        let result = gen_freq.expected_heterozygosity();
+        println!(
+            "computed value: {:?}, expected: {:?} +- {:?}",
+            result,
+            mc.mean(),
+            mc.error()
+        );
+        assert!((result - mc.mean()).abs() < 4. * mc.error());
+    }
+
+    #[test_case(N_REDNECK, U, -0.01, 0.5, 0., 1. - 1. / (2. * N_REDNECK as f64); "redneck unfixed")]
+    #[test_case(1000, 0.0001, 0.01, 0.5, 1. / 2000., 1.; "beneficial without singletons")]
+    #[test_case(1000, 0.0001, -0.001, 0.9, 0.1, 0.9; "interior window")]
+    #[test_case(1000, 0.00001, -0.01, 0.5, 0.01, 0.99; "window with little mass")]
+    fn expected_heterozygosity_between(
+        population: u64,
+        mutation_rate: f64,
+        selection: f64,
+        dominance: f64,
+        lower: f64,
+        upper: f64,
+    ) {
+        let gen_freq = GeneticFreq::new(population, mutation_rate, selection, dominance).unwrap();
+        let mut rng = crate::tests::rng(3);
+        let samples = 20_000;
+        let realizations: Vec<f64> = (0..samples)
+            .map(|_| gen_freq.sample_between(lower, upper, &mut rng))
+            .collect();
+        assert!(realizations.iter().all(|x| lower <= *x && *x <= upper));
+        let expected: average::Variance = realizations.iter().map(|x| 2. * x * (1. - x)).collect();
+
+        let result = gen_freq.expected_heterozygosity_between(lower, upper);
+        println!("computed value: {:?}, expected: {:?}", result, expected);
+        assert!((result - expected.mean()).abs() < 4. * expected.error());
+    }
+
+    #[test]
+    fn pdf_outside_domain() {
+        let gen_freq = GeneticFreq::new(1000, 0.00001, -0.00001, 0.5).unwrap();
+
+        assert_eq!(gen_freq.pdf(-0.1), 0.);
+        assert_eq!(gen_freq.pdf(1.1), 0.);
+        assert_eq!(gen_freq.ln_pdf(1.1), f64::NEG_INFINITY);
+    }
 }
@@ -2,16 +2,22 @@
 use average::Merge;
 use rand::distributions::Distribution;
 use rand::Rng;
+use statrs::distribution::Continuous;
 use statrs::statistics::{Max, Min};
 
 // Structs
+use crate::constants::EPS;
 use crate::error::{Result, StatsError};
 use std::f64;
 
 // Crates
+use crate::distribution::helper::{self, relative_integral};
 use rayon::prelude::*;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+// Constants
+use super::SELECTION_TAIL;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Selection {
     Fixed(f64),
     SkewNormal {
@@ -20,6 +26,244 @@ pub enum Selection {
         shape: f64,
         bounds: Option<(f64, f64)>,
     },
+    /// Deleterious selection `-X`, where `X` follows a Gamma distribution
+    /// with the given shape and scale, so that its mean is `shape * scale`.
+    Gamma {
+        shape: f64,
+        scale: f64,
+    },
+    /// Selection `X` with probability `beneficial` and `-X` otherwise,
+    /// where `X` follows a Gamma distribution with the given shape and scale.
+    ReflectedGamma {
+        shape: f64,
+        scale: f64,
+        beneficial: f64,
+    },
+    /// Selection drawn from one of the components, chosen with probability proportional to its weight.
+    ///
+    /// Components can be any selection, including mixtures themselves.
+    Mixture(Vec<(f64, Selection)>),
+}
+
+impl Selection {
+    /// Checks that the parameters are valid, including those of the components of a mixture.
+    pub(crate) fn check(&self) -> Result<()> {
+        match *self {
+            Selection::Fixed(s) => {
+                if !s.is_finite() {
+                    return Err(StatsError::BadParams);
+                }
+            }
+            Selection::SkewNormal {
+                location,
+                scale,
+                shape,
+                bounds,
+            } => {
+                if !(location.is_finite() && scale > 0. && scale.is_finite() && shape.is_finite()) {
+                    return Err(StatsError::BadParams);
+                }
+                if let Some((lower_bound, upper_bound)) = bounds {
+                    let random_selection = crate::SkewNormal::new(location, scale, shape)?;
+                    if upper_bound < lower_bound
+                        || random_selection.probability_between(lower_bound, upper_bound) <= 0.
+                    {
+                        return Err(StatsError::BadParams);
+                    }
+                }
+            }
+            Selection::Gamma { shape, scale } => {
+                if !(shape > 0. && shape.is_finite() && scale > 0. && scale.is_finite()) {
+                    return Err(StatsError::BadParams);
+                }
+            }
+            Selection::ReflectedGamma {
+                shape,
+                scale,
+                beneficial,
+            } => {
+                if !(shape > 0.
+                    && shape.is_finite()
+                    && scale > 0.
+                    && scale.is_finite()
+                    && (0.0..=1.0).contains(&beneficial))
+                {
+                    return Err(StatsError::BadParams);
+                }
+            }
+            Selection::Mixture(ref components) => {
+                let total: f64 = components.iter().map(|(weight, _)| weight).sum();
+                if !(total > 0. && total.is_finite())
+                    || components.iter().any(|(weight, _)| *weight < 0.)
+                {
+                    return Err(StatsError::BadParams);
+                }
+                for (_, component) in components {
+                    component.check()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Samples a selection coefficient.
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match *self {
+            Selection::Fixed(s) => s,
+            Selection::SkewNormal {
+                location,
+                scale,
+                shape,
+                bounds,
+            } => {
+                let random_selection = crate::SkewNormal::new(location, scale, shape).unwrap();
+                match bounds {
+                    Some((lower_bound, upper_bound)) => {
+                        random_selection.sample_between(lower_bound, upper_bound, rng)
+                    }
+                    None => random_selection.sample(rng),
+                }
+            }
+            Selection::Gamma { shape, scale } => {
+                -rand_distr::Gamma::new(shape, scale).unwrap().sample(rng)
+            }
+            Selection::ReflectedGamma {
+                shape,
+                scale,
+                beneficial,
+            } => {
+                let magnitude = rand_distr::Gamma::new(shape, scale).unwrap().sample(rng);
+                if rng.gen_bool(beneficial) {
+                    magnitude
+                } else {
+                    -magnitude
+                }
+            }
+            Selection::Mixture(ref components) => {
+                let total: f64 = components.iter().map(|(weight, _)| weight).sum();
+                let mut threshold = rng.gen::<f64>() * total;
+                for (weight, component) in components {
+                    if threshold < *weight {
+                        return component.sample(rng);
+                    }
+                    threshold -= weight;
+                }
+                // Rounding might leave the threshold above the last weight
+                let (_, component) = components
+                    .iter()
+                    .rev()
+                    .find(|(weight, _)| *weight > 0.)
+                    .unwrap();
+                component.sample(rng)
+            }
+        }
+    }
+
+    /// Returns the average of `conditional` over the selection, computed by numerical quadrature.
+    ///
+    /// A skew-normal selection is integrated over the range between its quantiles of probability `SELECTION_TAIL`
+    /// and `1 - SELECTION_TAIL`, intersected with its bounds if they are set,
+    /// so that narrow distributions of selection are resolved wherever they lie,
+    /// and a Gamma selection over the range given by `gamma_range`.
+    /// Each range is split by `selection_breakpoints` for a population of size `population`.
+    /// For a mixture, the averages of the components are combined with their weights.
+    pub(crate) fn average<F>(&self, population: u64, conditional: &F) -> quadrature::Output
+    where
+        F: Fn(f64) -> f64,
+    {
+        match *self {
+            Selection::Fixed(s) => quadrature::Output {
+                integral: conditional(s),
+                error_estimate: 0.,
+                num_function_evaluations: 1,
+            },
+            Selection::SkewNormal {
+                location,
+                scale,
+                shape,
+                bounds,
+            } => {
+                let random_selection = crate::SkewNormal::new(location, scale, shape).unwrap();
+                let (mut lower, mut upper) = random_selection.central_interval(SELECTION_TAIL);
+                if let Some((lower_bound, upper_bound)) = bounds {
+                    if lower_bound.max(lower) < upper_bound.min(upper) {
+                        lower = lower_bound.max(lower);
+                        upper = upper_bound.min(upper);
+                    } else {
+                        lower = lower_bound;
+                        upper = upper_bound;
+                    }
+                }
+                let breakpoints = selection_breakpoints(population, lower, upper);
+                average_over_selection(|s| random_selection.pdf(s), &breakpoints, conditional)
+            }
+            Selection::Gamma { shape, scale } => {
+                let magnitude = statrs::distribution::Gamma::new(shape, scale.recip()).unwrap();
+                let upper = gamma_range(shape, scale);
+                let breakpoints = selection_breakpoints(population, -upper, 0.);
+                average_over_selection(|s| magnitude.pdf(-s), &breakpoints, conditional)
+            }
+            Selection::ReflectedGamma {
+                shape,
+                scale,
+                beneficial,
+            } => {
+                let magnitude = statrs::distribution::Gamma::new(shape, scale.recip()).unwrap();
+                let upper = gamma_range(shape, scale);
+                let density = |s: f64| {
+                    if s > 0. {
+                        beneficial * magnitude.pdf(s)
+                    } else {
+                        (1. - beneficial) * magnitude.pdf(-s)
+                    }
+                };
+                let breakpoints = selection_breakpoints(population, -upper, upper);
+                average_over_selection(density, &breakpoints, conditional)
+            }
+            Selection::Mixture(ref components) => {
+                let total: f64 = components.iter().map(|(weight, _)| weight).sum();
+                components.iter().fold(
+                    quadrature::Output {
+                        integral: 0.,
+                        error_estimate: 0.,
+                        num_function_evaluations: 0,
+                    },
+                    |output, (weight, component)| {
+                        let component_output = component.average(population, conditional);
+                        let proportion = weight / total;
+                        quadrature::Output {
+                            integral: output.integral + proportion * component_output.integral,
+                            error_estimate: output.error_estimate
+                                + proportion * component_output.error_estimate,
+                            num_function_evaluations: output.num_function_evaluations
+                                + component_output.num_function_evaluations,
+                        }
+                    },
+                )
+            }
+        }
+    }
+}
+
+/// Averages `conditional` over the selection with the given (possibly unnormalized) density,
+/// integrated over consecutive breakpoints.
+fn average_over_selection<D, F>(
+    density: D,
+    breakpoints: &[f64],
+    conditional: &F,
+) -> quadrature::Output
+where
+    D: Fn(f64) -> f64,
+    F: Fn(f64) -> f64,
+{
+    let mass = relative_integral(&density, breakpoints);
+    let weighted = relative_integral(|s| density(s) * conditional(s), breakpoints);
+    let integral = weighted.integral / mass.integral;
+    quadrature::Output {
+        integral,
+        error_estimate: (weighted.error_estimate + integral * mass.error_estimate) / mass.integral,
+        num_function_evaluations: mass.num_function_evaluations + weighted.num_function_evaluations,
+    }
 }
 
 impl std::fmt::Display for Selection {
@@ -41,17 +285,160 @@ impl std::fmt::Display for Selection {
                 };
                 write!(f, "{}", description)
             }
+            Selection::Gamma { shape, scale } => write!(
+                f,
+                "Gamma deleterious selection with shape {}, scale {}",
+                shape, scale
+            ),
+            Selection::ReflectedGamma {
+                shape,
+                scale,
+                beneficial,
+            } => write!(
+                f,
+                "Reflected Gamma selection with shape {}, scale {}, beneficial proportion {}",
+                shape, scale, beneficial
+            ),
+            Selection::Mixture(ref components) => {
+                write!(f, "Mixture of")?;
+                for (i, (weight, component)) in components.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { "," };
+                    write!(f, "{} {} of ({})", separator, weight, component)?;
+                }
+                Ok(())
+            }
         }
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Dominance {
     /// Fixed value.
     Fixed(f64),
     Sigmoid {
         rate: f64,
     },
+    /// Additive for beneficial mutations and `h = 0.5 * exp(rate * s)` for deleterious ones,
+    /// so that strongly deleterious mutations are recessive.
+    Exponential {
+        rate: f64,
+    },
+    /// Hyperbolic relationship `h = 1 / (2 + constant * |s|)` of Kacser and Burns,
+    /// where strongly selected mutations are more recessive.
+    KacserBurns {
+        constant: f64,
+    },
+    /// Linear interpolation between the points `(s, h)`, sorted by selection,
+    /// and constant beyond the first and last points.
+    PiecewiseLinear(Vec<(f64, f64)>),
+    /// Step function given by the points `(s, h)`, sorted by selection:
+    /// the dominance of the largest tabulated selection not above `s`,
+    /// or of the first point if `s` is below all of them.
+    Tabulated(Vec<(f64, f64)>),
+    /// Random dominance following a Beta distribution, independently of the selection.
+    Beta {
+        alpha: f64,
+        beta: f64,
+    },
+}
+
+impl Dominance {
+    /// Checks that the parameters are valid and that the dominance is always in [0, 1].
+    pub(crate) fn check(&self) -> Result<()> {
+        match *self {
+            Dominance::Fixed(h) => {
+                if !(0.0..=1.0).contains(&h) {
+                    return Err(StatsError::BadParams);
+                }
+            }
+            Dominance::Sigmoid { rate } | Dominance::Exponential { rate } => {
+                if !(rate >= 0. && rate.is_finite()) {
+                    return Err(StatsError::BadParams);
+                }
+            }
+            Dominance::KacserBurns { constant } => {
+                if !(constant >= 0. && constant.is_finite()) {
+                    return Err(StatsError::BadParams);
+                }
+            }
+            Dominance::PiecewiseLinear(ref points) | Dominance::Tabulated(ref points) => {
+                if points.is_empty()
+                    || points
+                        .iter()
+                        .any(|(s, h)| !s.is_finite() || !(0.0..=1.0).contains(h))
+                    || points.windows(2).any(|pair| pair[0].0 >= pair[1].0)
+                {
+                    return Err(StatsError::BadParams);
+                }
+            }
+            Dominance::Beta { alpha, beta } => {
+                if !(alpha > 0. && alpha.is_finite() && beta > 0. && beta.is_finite()) {
+                    return Err(StatsError::BadParams);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the dominance coefficient for the given selection, or `None` if it is random.
+    pub(crate) fn coefficient(&self, selection: f64) -> Option<f64> {
+        match *self {
+            Dominance::Fixed(h) => Some(h),
+            Dominance::Sigmoid { rate } => Some(1. / (1. + (-rate * selection).exp())),
+            Dominance::Exponential { rate } => {
+                if selection >= 0. {
+                    Some(0.5)
+                } else {
+                    Some(0.5 * (rate * selection).exp())
+                }
+            }
+            Dominance::KacserBurns { constant } => Some(1. / (2. + constant * selection.abs())),
+            Dominance::PiecewiseLinear(ref points) => {
+                let after = points.iter().position(|(s, _)| *s > selection);
+                Some(match after {
+                    Some(0) => points[0].1,
+                    None => points[points.len() - 1].1,
+                    Some(i) => {
+                        let ((s_0, h_0), (s_1, h_1)) = (points[i - 1], points[i]);
+                        h_0 + (h_1 - h_0) * (selection - s_0) / (s_1 - s_0)
+                    }
+                })
+            }
+            Dominance::Tabulated(ref points) => {
+                let after = points.iter().position(|(s, _)| *s > selection);
+                Some(match after {
+                    Some(0) => points[0].1,
+                    None => points[points.len() - 1].1,
+                    Some(i) => points[i - 1].1,
+                })
+            }
+            Dominance::Beta { .. } => None,
+        }
+    }
+
+    /// Samples a dominance coefficient for the given selection.
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, selection: f64, rng: &mut R) -> f64 {
+        match *self {
+            Dominance::Beta { alpha, beta } => crate::Beta::new(alpha, beta).unwrap().sample(rng),
+            _ => self.coefficient(selection).unwrap(),
+        }
+    }
+
+    /// Returns the expectation of `f(h)` for the dominance `h` of the given selection.
+    ///
+    /// For random dominance, it is computed by numerical quadrature against the Beta density.
+    pub(crate) fn expectation<F>(&self, selection: f64, f: F) -> f64
+    where
+        F: Fn(f64) -> f64,
+    {
+        match *self {
+            Dominance::Beta { alpha, beta } => {
+                let density = crate::Beta::new(alpha, beta).unwrap();
+                quadrature::integrate(|h| density.pdf(h) * f(h), 0., 1., EPS).integral
+            }
+            _ => f(self.coefficient(selection).unwrap()),
+        }
+    }
 }
 
 impl std::fmt::Display for Dominance {
@@ -59,6 +446,21 @@ impl std::fmt::Display for Dominance {
         match *self {
             Dominance::Fixed(h) => write!(f, "Fixed dominance {}", h),
             Dominance::Sigmoid { rate: beta } => write!(f, "Sigmoid with rate {}", beta),
+            Dominance::Exponential { rate } => write!(f, "Exponential with rate {}", rate),
+            Dominance::KacserBurns { constant } => {
+                write!(f, "Kacser-Burns with constant {}", constant)
+            }
+            Dominance::PiecewiseLinear(ref points) => {
+                write!(f, "Piecewise linear through {:?}", points)
+            }
+            Dominance::Tabulated(ref points) => write!(f, "Tabulated at {:?}", points),
+            Dominance::Beta { alpha, beta } => {
+                write!(
+                    f,
+                    "Beta random dominance with parameters {}, {}",
+                    alpha, beta
+                )
+            }
         }
     }
 }
@@ -69,7 +471,11 @@ impl std::fmt::Display for Dominance {
 ///
 /// The density of the allele frequency `x` is proportinal to
 /// `exp(2 * population * selection * (x^2 + 2 * dominance * x * (1 - x)))
-/// * x^(4 * population * mutation_rate - 1) * (1 - x)^(4 population * mutation_rate - 1)`.
+/// * x^(4 * population * mutation_rate - 1) * (1 - x)^(4 population * mutation_rate - 1)`,
+/// possibly restricted to a frequency window `[lower, upper]`.
+///
+/// The window conditions the allele frequency given the selection,
+/// so that the distribution of the selection is not affected by it.
 ///
 /// # Examples
 ///
@@ -82,12 +488,13 @@ impl std::fmt::Display for Dominance {
 /// let dominance = Dominance::Fixed(0.5);
 /// let hetero = Heterozygosity::new(population, mutation_rate, selection, dominance).unwrap();
 /// ```
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Heterozygosity {
     population: u64,    // N
     mutation_rate: f64, // U
     selection: Selection,
     dominance: Dominance,
+    frequency_bounds: (f64, f64),
 }
 
 impl Heterozygosity {
@@ -96,7 +503,9 @@ impl Heterozygosity {
     ///
     /// # Errors
     ///
-    /// Returns an error if `dominance` is not in the interval [0, 1]
+    /// Returns an error if `population` is zero, if `mutation_rate` is not positive,
+    /// or if the parameters of `selection` or `dominance` are not valid,
+    /// for instance if the dominance is not in the interval [0, 1].
     ///
     /// # Examples
     ///
@@ -108,7 +517,7 @@ impl Heterozygosity {
     /// let selection = Selection::Fixed(0.0);
     /// let dominance = Dominance::Fixed(0.5);
     ///
-    /// let result = Heterozygosity::new(population, mutation_rate, selection, dominance);
+    /// let result = Heterozygosity::new(population, mutation_rate, selection.clone(), dominance);
     /// assert!(result.is_ok());
     ///
     /// let dominance = Dominance::Fixed(-0.5);
@@ -121,100 +530,249 @@ impl Heterozygosity {
         selection: Selection,
         dominance: Dominance,
     ) -> Result<Self> {
-        match selection {
-            Selection::Fixed(s) => {
-                if s.is_nan() {
-                    return Err(StatsError::BadParams);
-                }
-            }
-            Selection::SkewNormal {
-                location,
-                scale,
-                shape,
-                bounds,
-            } => {
-                if location.is_nan() || scale.is_nan() || scale <= 0.0 || shape.is_nan() {
-                    return Err(StatsError::BadParams);
-                }
-                if let Some((lower_bound, upper_bound)) = bounds {
-                    if upper_bound < lower_bound {
-                        return Err(StatsError::BadParams);
-                    }
-                }
-            }
-        }
-        match dominance {
-            Dominance::Fixed(h) => {
-                if h.is_nan() || h < 0.0 {
-                    return Err(StatsError::BadParams);
-                }
-            }
-            Dominance::Sigmoid { rate } => {
-                if rate < 0.0 || rate.is_nan() {
-                    return Err(StatsError::BadParams);
-                }
-            }
+        Heterozygosity::new_truncated(population, mutation_rate, selection, dominance, 0., 1.)
+    }
+
+    /// Constructs a new Heterozygosity distribution whose allele frequency is restricted
+    /// to the window `[lower, upper]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameters are not valid for `new`,
+    /// or if `lower` and `upper` do not satisfy `0 <= lower < upper <= 1`.
+    ///
+    /// # Examples
+    ///
+    /// Excluding singletons.
+    /// ```
+    /// use sandpiper::{Heterozygosity, Selection, Dominance};
+    ///
+    /// let population = 1000;
+    /// let mutation_rate = 0.00001;
+    /// let selection = Selection::Fixed(-0.001);
+    /// let dominance = Dominance::Fixed(0.5);
+    /// let lower = 1. / (2. * population as f64);
+    ///
+    /// let result = Heterozygosity::new_truncated(population, mutation_rate, selection.clone(), dominance.clone(), lower, 1.);
+    /// assert!(result.is_ok());
+    ///
+    /// let result = Heterozygosity::new_truncated(population, mutation_rate, selection, dominance, 0.5, 0.1);
+    /// assert!(result.is_err());
+    /// ```
+    pub fn new_truncated(
+        population: u64,
+        mutation_rate: f64,
+        selection: Selection,
+        dominance: Dominance,
+        lower: f64,
+        upper: f64,
+    ) -> Result<Self> {
+        if population == 0
+            || mutation_rate.is_nan()
+            || mutation_rate <= 0.
+            || !(0. <= lower && lower < upper && upper <= 1.)
+        {
+            return Err(StatsError::BadParams);
         }
+        selection.check()?;
+        dominance.check()?;
         Ok(Heterozygosity {
             population,
             mutation_rate,
             selection,
             dominance,
+            frequency_bounds: (lower, upper),
         })
     }
 
+    /// Constructs a new Heterozygosity distribution whose allele frequency is restricted
+    /// to `[0, upper_bound]`, following one of the presets of `UpperBound`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameters are not valid for `new`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::{Heterozygosity, Selection, Dominance, UpperBound};
+    ///
+    /// let population = 1000;
+    /// let mutation_rate = 0.00001;
+    /// let selection = Selection::Fixed(0.0);
+    /// let dominance = Dominance::Fixed(0.5);
+    ///
+    /// let hetero = Heterozygosity::unfixed(population, mutation_rate, selection, dominance, UpperBound::Smallest).unwrap();
+    /// assert_eq!(hetero.frequency_bounds(), (0., 1. - 1. / 2000.));
+    /// ```
+    pub fn unfixed(
+        population: u64,
+        mutation_rate: f64,
+        selection: Selection,
+        dominance: Dominance,
+        upper_bound: UpperBound,
+    ) -> Result<Self> {
+        Heterozygosity::new_truncated(
+            population,
+            mutation_rate,
+            selection,
+            dominance,
+            0.,
+            upper_bound.frequency(population),
+        )
+    }
+
+    /// Returns the window `[lower, upper]` the allele frequency is restricted to.
+    pub fn frequency_bounds(&self) -> (f64, f64) {
+        self.frequency_bounds
+    }
+
     /// Samples from the selection 's'.
     pub fn sample_selection<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
-        match self.selection {
-            Selection::Fixed(s) => s,
-            Selection::SkewNormal {
-                location,
-                scale,
-                shape,
-                bounds,
-            } => {
-                let random_selection = crate::SkewNormal::new(location, scale, shape).unwrap();
-                let mut proposal = random_selection.sample(rng);
-                if let Some((lower_bound, upper_bound)) = bounds {
-                    while (proposal < lower_bound) || (proposal > upper_bound) {
-                        proposal = random_selection.sample(rng);
-                    }
-                }
-                proposal
-            }
-        }
+        self.selection.sample(rng)
     }
 
     /// Samples from the allele frequency 'x' that will lead to heterozygosity '2 x (1 - x)'.
     pub fn sample_frequency<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let (lower, upper) = self.frequency_bounds;
+        self.sample_genetic_freq(rng)
+            .sample_between(lower, upper, rng)
+    }
+
+    /// Samples the logarithms `(ln x, ln(1 - x))` of the allele frequency and its complement,
+    /// which are accurate even when `x` or `1 - x` are too small to be represented.
+    pub fn sample_ln_frequency<R: Rng + ?Sized>(&self, rng: &mut R) -> (f64, f64) {
+        let (lower, upper) = self.frequency_bounds;
+        self.sample_genetic_freq(rng)
+            .sample_ln_between(lower, upper, rng)
+    }
+
+    /// Samples the selection and dominance, returning the distribution of the allele frequency given them.
+    fn sample_genetic_freq<R: Rng + ?Sized>(&self, rng: &mut R) -> crate::GeneticFreq {
         let selection = self.sample_selection(rng);
-        let dominance = match self.dominance {
-            Dominance::Fixed(h) => h,
-            Dominance::Sigmoid { rate } => 1. / (1. + (-rate * selection).exp()),
-        };
+        let dominance = self.dominance.sample(selection, rng);
 
-        crate::GeneticFreq::new(self.population, self.mutation_rate, selection, dominance)
-            .unwrap()
-            .sample(rng)
+        crate::GeneticFreq::new(self.population, self.mutation_rate, selection, dominance).unwrap()
+    }
+
+    /// Returns the expected heterozygosity `E[2x(1-x)]`, computed by numerical quadrature.
+    ///
+    /// For a fixed selection, this is the conditional expectation of `GeneticFreq` given the frequency window.
+    /// Otherwise, the conditional expectation given the selection is integrated against its density.
+    /// A skew-normal selection is restricted to `bounds` and to the range between its quantiles
+    /// of probability `1e-12` and `1 - 1e-12`,
+    /// and a Gamma selection to the range beyond which its mass is negligible.
+    /// For a mixture, the expectations of the components are averaged with their weights.
+    /// A random dominance is integrated for each selection, which multiplies the cost.
+    /// The integration range is split at geometric distances from neutrality, where the conditional
+    /// expectation changes on the scale `1 / N`.
+    ///
+    /// The error estimate accounts for the quadrature over the selection,
+    /// while each conditional expectation is computed up to rounding error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::{Heterozygosity, Selection, Dominance};
+    ///
+    /// let selection = Selection::SkewNormal { location: -1e-3, scale: 1e-3, shape: 1., bounds: None };
+    /// let hetero = Heterozygosity::new(1000, 1e-5, selection, Dominance::Sigmoid { rate: 100. }).unwrap();
+    /// let output = hetero.expected_heterozygosity();
+    /// assert!(output.integral > 0. && output.integral < 0.5);
+    /// ```
+    pub fn expected_heterozygosity(&self) -> quadrature::Output {
+        self.selection
+            .average(self.population, &|s| self.conditional_heterozygosity(s))
+    }
+
+    /// Returns the expected heterozygosity given the selection,
+    /// averaged over the dominance if it is random.
+    fn conditional_heterozygosity(&self, selection: f64) -> f64 {
+        self.dominance.expectation(selection, |dominance| {
+            let gen_freq =
+                crate::GeneticFreq::new(self.population, self.mutation_rate, selection, dominance)
+                    .unwrap();
+            match self.frequency_bounds {
+                (lower, upper) if lower > 0. || upper < 1. => {
+                    gen_freq.expected_heterozygosity_between(lower, upper)
+                }
+                _ => gen_freq.expected_heterozygosity(),
+            }
+        })
     }
 
     /// Returns a empirical average with the given number of samples.
+    ///
+    /// The seed is drawn from `thread_rng`. See `mc_mean_seeded` for reproducible results.
     pub fn mc_mean(&self, samples: usize) -> average::Mean {
-        (0..samples)
+        self.mc_mean_seeded(samples, rand::thread_rng().gen())
+    }
+
+    /// Returns a empirical average with the given number of samples, reproducibly from `seed`.
+    ///
+    /// The `i`-th sample is drawn from `helper::stream_rng(seed, i)`,
+    /// so the result only depends on the seed, not on the number of threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::{Heterozygosity, Selection, Dominance};
+    ///
+    /// let hetero = Heterozygosity::new(1000, 1e-5, Selection::Fixed(-1e-3), Dominance::Fixed(0.5)).unwrap();
+    /// assert_eq!(hetero.mc_mean_seeded(100, 1).mean(), hetero.mc_mean_seeded(100, 1).mean());
+    /// ```
+    pub fn mc_mean_seeded(&self, samples: usize, seed: u64) -> average::Mean {
+        self.mc_mean_streams(seed, 0..samples as u64)
+    }
+
+    /// Returns the empirical average of the samples drawn from the given streams of `seed`.
+    fn mc_mean_streams(&self, seed: u64, streams: std::ops::Range<u64>) -> average::Mean {
+        streams
             .into_par_iter()
-            .map(|_| self.sample(&mut rand::thread_rng()))
+            .map(|i| self.sample(&mut helper::stream_rng(seed, i)))
             .collect::<Vec<f64>>()
             .iter()
             .collect()
     }
 
     /// Approximates the expectation with approximated variance to match up the error limit given.
+    ///
+    /// The seed is drawn from `thread_rng`. See `mc_approx_mean_seeded` for reproducible results.
     pub fn mc_approx_mean(&self, variance_samples: usize, error_limit: f64) -> average::Variance {
+        self.mc_approx_mean_seeded(variance_samples, error_limit, rand::thread_rng().gen())
+    }
+
+    /// Approximates the expectation with approximated variance to match up the error limit given,
+    /// reproducibly from `seed`.
+    ///
+    /// Each of the `variance_samples` empirical averages has its own seed derived from `seed`,
+    /// and each round draws twice as many new samples as the previous one, continuing with the following streams.
+    /// Therefore, the result only depends on the seed, not on the number of threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::{Heterozygosity, Selection, Dominance};
+    ///
+    /// let hetero = Heterozygosity::new(1000, 1e-5, Selection::Fixed(-1e-3), Dominance::Fixed(0.5)).unwrap();
+    /// let variance = hetero.mc_approx_mean_seeded(10, 1e-3, 1);
+    /// assert_eq!(variance.mean(), hetero.mc_approx_mean_seeded(10, 1e-3, 1).mean());
+    /// ```
+    pub fn mc_approx_mean_seeded(
+        &self,
+        variance_samples: usize,
+        error_limit: f64,
+        seed: u64,
+    ) -> average::Variance {
         let mut samples = 1000;
+        let mut drawn = samples;
+        let seeds = (0..variance_samples as u64)
+            .map(|index| helper::sub_seed(seed, index))
+            .collect::<Vec<u64>>();
         // Sample
-        let mut mc_mean_samples = (0..variance_samples)
-            .into_par_iter()
-            .map(|_| self.mc_mean(samples))
+        let mut mc_mean_samples = seeds
+            .par_iter()
+            .map(|seed| self.mc_mean_streams(*seed, 0..samples))
             .collect::<Vec<average::Mean>>();
         // Summarize
         let mut variance: average::Variance = mc_mean_samples
@@ -226,8 +784,13 @@ impl Heterozygosity {
             // Enhace samples
             mc_mean_samples = mc_mean_samples
                 .into_par_iter()
-                .update(|mc_mean_sample| mc_mean_sample.merge(&self.mc_mean(samples)))
+                .zip(&seeds)
+                .map(|(mut mc_mean_sample, seed)| {
+                    mc_mean_sample.merge(&self.mc_mean_streams(*seed, drawn..drawn + samples));
+                    mc_mean_sample
+                })
                 .collect::<Vec<average::Mean>>();
+            drawn += samples;
             // Summarize
             variance = mc_mean_samples
                 .iter()
@@ -239,10 +802,12 @@ impl Heterozygosity {
 }
 
 impl Distribution<f64> for Heterozygosity {
+    /// Samples `2 x (1 - x)`, computed from the logarithms of `x` and `1 - x`,
+    /// so that it is not rounded to zero when `1 - x` is too small to be represented.
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
-        let x = self.sample_frequency(rng);
+        let (ln_x, ln_y) = self.sample_ln_frequency(rng);
 
-        2. * x * (1. - x)
+        (f64::consts::LN_2 + ln_x + ln_y).exp()
     }
 }
 
@@ -260,6 +825,36 @@ impl Max<f64> for Heterozygosity {
     }
 }
 
+/// Returns the points splitting the range of integration `[lower, upper]` over the selection.
+///
+/// Inside the range, neutrality and the selections at `10^k / (2N)` from it are added,
+/// since the conditional expectation given the selection changes on the scale `1 / N`.
+pub(crate) fn selection_breakpoints(population: u64, lower: f64, upper: f64) -> Vec<f64> {
+    let mut breakpoints = vec![lower, upper];
+    if lower < 0. && 0. < upper {
+        breakpoints.push(0.);
+    }
+    let mut distance = 1. / (2. * population as f64);
+    while distance < lower.abs().max(upper.abs()) {
+        for &point in &[-distance, distance] {
+            if lower < point && point < upper {
+                breakpoints.push(point);
+            }
+        }
+        distance *= 10.;
+    }
+    breakpoints.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    breakpoints
+}
+
+/// Returns the magnitude of selection beyond which a Gamma distribution has negligible mass,
+/// namely ten standard deviations beyond the mean plus forty scales.
+pub(crate) fn gamma_range(shape: f64, scale: f64) -> f64 {
+    scale * (shape + 10. * shape.sqrt() + 40.)
+}
+
+/// Presets for the largest allele frequency of `Heterozygosity::unfixed`,
+/// which excludes alleles close to fixation.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum UpperBound {
     Smallest, // 1 - 1/(2N)
@@ -267,220 +862,358 @@ pub enum UpperBound {
     Largest,  // 1
 }
 
-/// Polymorphisms in a population with a trait that is not fixed.
-///
-/// If `x` is the allele frequency, then the heterozygosity is `2x(1-x)`.
-///
-/// The density of the allele frequency `x` is proportinal to
-/// `exp(2 * population * selection * (x^2 + 2 * dominance * x * (1 - x)))
-/// * x^(4 * population * mutation_rate - 1) * (1 - x)^(4 population * mutation_rate - 1)`,
-/// and we add the restriction that `x > 1 - 1/(2N)`, where `N` is the population size.
-///
-/// # Examples
-///
-/// ```
-/// use sandpiper::{UnfixedHeterozygosity, Selection, Dominance};
-///
-/// let population = 1000;
-/// let mutation_rate = 0.00001;
-/// let selection = Selection::Fixed(0.0);
-/// let dominance = Dominance::Fixed(0.5);
-/// let unfixed_hetero = UnfixedHeterozygosity::new(population, mutation_rate, selection, dominance).unwrap();
-/// ```
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct UnfixedHeterozygosity {
-    population: u64,    // N
-    mutation_rate: f64, // U
-    selection: Selection,
-    dominance: Dominance,
-    frequency_upper_bound: f64,
+impl UpperBound {
+    /// Returns the largest allele frequency for the given population size.
+    pub fn frequency(&self, population: u64) -> f64 {
+        match *self {
+            UpperBound::Smallest => 1. - 1. / (2. * population as f64),
+            UpperBound::Midpoint => 1. - 1. / (4. * population as f64),
+            UpperBound::Largest => 1.,
+        }
+    }
 }
 
-impl UnfixedHeterozygosity {
-    /// Constructs a new UnfixedHeterozygosity distribution.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if `dominance` is not in the interval [0, 1]
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use sandpiper::{Heterozygosity, Selection, Dominance};
-    ///
-    /// let population = 1000;
-    /// let mutation_rate = 0.00001;
-    /// let selection = Selection::Fixed(0.0);
-    /// let dominance = Dominance::Fixed(0.5);
-    /// let upper_bound = sandpiper::UpperBound::Smallest;
-    ///
-    /// let result = UnfixedHeterozygosity::new(population, mutation_rate, selection, dominance, upper_bound);
-    /// assert!(result.is_ok());
-    ///
-    /// let dominance = Dominance::Fixed(-0.5);
-    /// let result = Heterozygosity::new(population, mutation_rate, selection, dominance);
-    /// assert!(result.is_err());
-    /// ```
-    pub fn new(
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(1000, 1e-5; "small shape")]
+    #[test_case(1000, 1e-4; "medium shape")]
+    fn expected_heterozygosity_neutral(population: u64, mutation_rate: f64) {
+        let hetero = Heterozygosity::new(
+            population,
+            mutation_rate,
+            Selection::Fixed(0.),
+            Dominance::Fixed(0.5),
+        )
+        .unwrap();
+        let shape = 4. * population as f64 * mutation_rate;
+        let expected = shape / (2. * shape + 1.);
+
+        let result = hetero.expected_heterozygosity().integral;
+        assert!((result - expected).abs() < 1e-10 * expected);
+    }
+
+    #[test_case(1000, 1e-4, 0., 1e-2, UpperBound::Smallest; "smallest")]
+    #[test_case(1000, 1e-4, 0., 1e-2, UpperBound::Midpoint; "midpoint")]
+    #[test_case(1000, 1e-4, 0., 1e-2, UpperBound::Largest; "largest")]
+    fn unfixed_mc(
         population: u64,
         mutation_rate: f64,
-        selection: Selection,
-        dominance: Dominance,
+        location: f64,
+        scale: f64,
         upper_bound: UpperBound,
-    ) -> Result<Self> {
-        match selection {
-            Selection::Fixed(s) => {
-                if s.is_nan() {
-                    return Err(StatsError::BadParams);
-                }
-            }
-            Selection::SkewNormal {
-                location,
-                scale,
-                shape,
-                bounds,
-            } => {
-                if location.is_nan() || scale.is_nan() || scale <= 0.0 || shape.is_nan() {
-                    return Err(StatsError::BadParams);
-                }
-                if let Some((lower_bound, upper_bound)) = bounds {
-                    if upper_bound < lower_bound {
-                        return Err(StatsError::BadParams);
-                    }
-                }
-            }
-        }
-        match dominance {
-            Dominance::Fixed(h) => {
-                if h.is_nan() || h < 0.0 {
-                    return Err(StatsError::BadParams);
-                }
-            }
-            Dominance::Sigmoid { rate } => {
-                if rate < 0.0 || rate.is_nan() {
-                    return Err(StatsError::BadParams);
-                }
-            }
-        }
-        let frequency_upper_bound = match upper_bound {
-            UpperBound::Smallest => 1. - 1. / (2. * population as f64),
-            UpperBound::Midpoint => 1. - 1. / (4. * population as f64),
-            UpperBound::Largest => 1.,
+    ) {
+        let selection = Selection::SkewNormal {
+            location,
+            scale,
+            shape: 0.,
+            bounds: None,
         };
-
-        Ok(UnfixedHeterozygosity {
+        let hetero = Heterozygosity::unfixed(
             population,
             mutation_rate,
             selection,
-            dominance,
-            frequency_upper_bound,
-        })
+            Dominance::Fixed(0.5),
+            upper_bound,
+        )
+        .unwrap();
+        let upper = upper_bound.frequency(population);
+        let mut rng = crate::tests::rng(4);
+        let realizations: Vec<f64> = (0..20_000)
+            .map(|_| hetero.sample_frequency(&mut rng))
+            .collect();
+        assert!(realizations.iter().all(|x| *x <= upper));
+        let mc: average::Variance = realizations.iter().map(|x| 2. * x * (1. - x)).collect();
+
+        let result = hetero.expected_heterozygosity();
+        println!("computed value: {:?}, expected: {:?}", result, mc);
+        assert!((result.integral - mc.mean()).abs() < 4. * mc.error());
     }
 
-    /// Samples from the selection 's'.
-    pub fn sample_selection<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
-        match self.selection {
-            Selection::Fixed(s) => s,
-            Selection::SkewNormal {
-                location,
-                scale,
-                shape,
-                bounds,
-            } => {
-                let random_selection = crate::SkewNormal::new(location, scale, shape).unwrap();
-                let mut proposal = random_selection.sample(rng);
-                if let Some((lower_bound, upper_bound)) = bounds {
-                    while (proposal < lower_bound) || (proposal > upper_bound) {
-                        proposal = random_selection.sample(rng);
-                    }
-                }
-                proposal
-            }
-        }
+    #[test_case(Selection::Gamma { shape: 0.3, scale: 1e-2 }; "gamma")]
+    #[test_case(Selection::Gamma { shape: 2., scale: 1e-4 }; "gamma nearly neutral")]
+    #[test_case(Selection::ReflectedGamma { shape: 0.3, scale: 1e-3, beneficial: 0.1 }; "reflected gamma")]
+    fn expected_heterozygosity_gamma(selection: Selection) {
+        let hetero = Heterozygosity::new(1000, 1e-4, selection, Dominance::Fixed(0.5)).unwrap();
+        let mc: average::Variance = (&hetero)
+            .sample_iter(crate::tests::rng(5))
+            .take(200_000)
+            .collect();
+
+        let result = hetero.expected_heterozygosity();
+        println!("computed value: {:?}, expected: {:?}", result, mc);
+        assert!((result.integral - mc.mean()).abs() < 4. * mc.error());
     }
 
-    /// Samples from the allele frequency 'x' that will lead to heterozygosity '2 x (1 - x)'.
-    pub fn sample_frequency<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
-        let selection = self.sample_selection(rng);
-        let dominance = match self.dominance {
-            Dominance::Fixed(h) => h,
-            Dominance::Sigmoid { rate } => 1. / (1. + (-rate * selection).exp()),
+    #[test_case(Selection::SkewNormal { location: -1e-2, scale: 5e-3, shape: -1., bounds: None }; "skew normal")]
+    #[test_case(Selection::ReflectedGamma { shape: 0.3, scale: 1e-3, beneficial: 0.1 }; "reflected gamma")]
+    #[test_case(Selection::Mixture(vec![(0.5, Selection::Fixed(0.)), (0.5, Selection::Gamma { shape: 0.3, scale: 1e-2 })]); "mixture")]
+    fn average_of_zero(selection: Selection) {
+        let output = selection.average(1000, &|_| 0.);
+        assert_eq!(output.integral, 0.);
+        assert!(output.error_estimate.is_finite());
+    }
+
+    #[test]
+    fn expected_heterozygosity_mixture() {
+        let deleterious = Selection::SkewNormal {
+            location: -1e-2,
+            scale: 5e-3,
+            shape: -1.,
+            bounds: Some((-1., 0.)),
+        };
+        let beneficial = Selection::ReflectedGamma {
+            shape: 1.,
+            scale: 1e-3,
+            beneficial: 1.,
         };
+        let selection = Selection::Mixture(vec![
+            (0.3, Selection::Fixed(0.)),
+            (0.6, deleterious.clone()),
+            (0.1, beneficial.clone()),
+        ]);
+        let expected_given = |selection: Selection| {
+            Heterozygosity::new(1000, 1e-4, selection, Dominance::Fixed(0.5))
+                .unwrap()
+                .expected_heterozygosity()
+                .integral
+        };
+        let hetero = Heterozygosity::new(1000, 1e-4, selection, Dominance::Fixed(0.5)).unwrap();
 
-        let random_frequency =
-            crate::GeneticFreq::new(self.population, self.mutation_rate, selection, dominance)
-                .unwrap();
-        let mut proposal = random_frequency.sample(rng);
-        while proposal > self.frequency_upper_bound {
-            proposal = random_frequency.sample(rng);
-        }
-        proposal
+        let result = hetero.expected_heterozygosity().integral;
+        let expected = 0.3 * expected_given(Selection::Fixed(0.))
+            + 0.6 * expected_given(deleterious)
+            + 0.1 * expected_given(beneficial);
+        assert!((result - expected).abs() < 1e-12);
+
+        let mc: average::Variance = (&hetero)
+            .sample_iter(crate::tests::rng(7))
+            .take(200_000)
+            .collect();
+        println!("computed value: {:?}, expected: {:?}", result, mc);
+        assert!((result - mc.mean()).abs() < 4. * mc.error());
     }
 
-    /// Returns a empirical average with the given number of samples.
-    pub fn mc_mean(&self, samples: usize) -> average::Mean {
-        (0..samples)
-            .into_par_iter()
-            .map(|_| self.sample(&mut rand::thread_rng()))
-            .collect::<Vec<f64>>()
-            .iter()
-            .collect()
+    #[test]
+    fn sample_mixture_proportions() {
+        let selection = Selection::Mixture(vec![
+            (3., Selection::Fixed(0.)),
+            (1., Selection::Fixed(-1.)),
+        ]);
+        let hetero = Heterozygosity::new(1000, 1e-4, selection, Dominance::Fixed(0.5)).unwrap();
+        let mut rng = crate::tests::rng(8);
+        let samples = 10_000;
+        let neutral = (0..samples)
+            .filter(|_| hetero.sample_selection(&mut rng) == 0.)
+            .count();
+        assert!((neutral as f64 / samples as f64 - 0.75).abs() < 0.02);
     }
 
-    /// Approximates the expectation with approximated variance to match up the error limit given.
-    pub fn mc_approx_mean(&self, variance_samples: usize, error_limit: f64) -> average::Variance {
-        let mut samples = 1000;
-        // Sample
-        let mut mc_mean_samples = (0..variance_samples)
-            .into_par_iter()
-            .map(|_| self.mc_mean(samples))
-            .collect::<Vec<average::Mean>>();
-        // Summarize
-        let mut variance: average::Variance = mc_mean_samples
-            .iter()
-            .map(|mc_mean_sample| mc_mean_sample.mean())
+    #[test_case(Selection::SkewNormal { location: -0.05, scale: 1e-5, shape: 0., bounds: Some((0., 1.)) }; "bounds without mass")]
+    #[test_case(Selection::SkewNormal { location: -0.05, scale: 1e-3, shape: 1e3, bounds: Some((-1., -0.06)) }; "bounds without mass by shape")]
+    #[test_case(Selection::Mixture(vec![]); "empty mixture")]
+    #[test_case(Selection::Mixture(vec![(-1., Selection::Fixed(0.)), (2., Selection::Fixed(0.))]); "negative weight")]
+    #[test_case(Selection::Mixture(vec![(1., Selection::Gamma { shape: -1., scale: 1. })]); "invalid component")]
+    #[test_case(Selection::Fixed(f64::NEG_INFINITY); "infinite fixed")]
+    #[test_case(Selection::SkewNormal { location: f64::INFINITY, scale: 1e-3, shape: 0., bounds: None }; "infinite location")]
+    #[test_case(Selection::Gamma { shape: 0., scale: 1e-2 }; "zero shape")]
+    #[test_case(Selection::Gamma { shape: 0.3, scale: f64::NAN }; "nan scale")]
+    #[test_case(Selection::ReflectedGamma { shape: 0.3, scale: 1e-3, beneficial: 1.5 }; "beneficial above one")]
+    fn new_invalid_selection(selection: Selection) {
+        assert!(Heterozygosity::new(1000, 1e-4, selection, Dominance::Fixed(0.5)).is_err());
+    }
+
+    #[test_case(0, 1e-4; "empty population")]
+    #[test_case(1000, 0.; "zero mutation rate")]
+    #[test_case(1000, f64::NAN; "nan mutation rate")]
+    fn new_invalid(population: u64, mutation_rate: f64) {
+        let result = Heterozygosity::new(
+            population,
+            mutation_rate,
+            Selection::Fixed(0.),
+            Dominance::Fixed(0.5),
+        );
+        assert!(matches!(result, Err(StatsError::BadParams)));
+    }
+
+    #[test]
+    fn sample_skew_normal_bounds_with_little_mass() {
+        let bounds = (-1., -0.05 - 2e-5);
+        let hetero = Heterozygosity::new(
+            1000,
+            1e-4,
+            Selection::SkewNormal {
+                location: -0.05,
+                scale: 1e-5,
+                shape: 5.,
+                bounds: Some(bounds),
+            },
+            Dominance::Fixed(0.5),
+        )
+        .unwrap();
+        let mut rng = crate::tests::rng(9);
+        assert!((0..100)
+            .map(|_| hetero.sample_selection(&mut rng))
+            .all(|s| bounds.0 <= s && s <= bounds.1));
+    }
+
+    #[test_case(Dominance::KacserBurns { constant: 100. }, -0.01, 1. / 3.; "kacser burns")]
+    #[test_case(Dominance::Exponential { rate: 100. }, -0.01, 0.5 * (-1f64).exp(); "exponential")]
+    #[test_case(Dominance::PiecewiseLinear(vec![(-0.1, 0.1), (0., 0.5), (0.1, 0.7)]), -0.05, 0.3; "linear interpolation")]
+    #[test_case(Dominance::PiecewiseLinear(vec![(-0.1, 0.1), (0., 0.5), (0.1, 0.7)]), 0.2, 0.7; "linear extrapolation")]
+    #[test_case(Dominance::Tabulated(vec![(-0.1, 0.1), (0., 0.5), (0.1, 0.7)]), -0.05, 0.1; "tabulated step")]
+    #[test_case(Dominance::Tabulated(vec![(-0.1, 0.1), (0., 0.5), (0.1, 0.7)]), -0.2, 0.1; "tabulated below")]
+    fn dominance_coefficient(dominance: Dominance, selection: f64, expected: f64) {
+        let result = dominance.coefficient(selection).unwrap();
+        assert!((result - expected).abs() < 1e-12);
+    }
+
+    #[test_case(Dominance::Fixed(1.5); "fixed above one")]
+    #[test_case(Dominance::KacserBurns { constant: -1. }; "negative constant")]
+    #[test_case(Dominance::Exponential { rate: f64::NAN }; "nan rate")]
+    #[test_case(Dominance::Sigmoid { rate: f64::INFINITY }; "infinite rate")]
+    #[test_case(Dominance::PiecewiseLinear(vec![]); "empty points")]
+    #[test_case(Dominance::PiecewiseLinear(vec![(0., 0.5), (0., 0.6)]); "repeated selection")]
+    #[test_case(Dominance::Tabulated(vec![(0., 1.5)]); "tabulated above one")]
+    #[test_case(Dominance::Beta { alpha: 0., beta: 1. }; "zero alpha")]
+    fn new_invalid_dominance(dominance: Dominance) {
+        assert!(Heterozygosity::new(1000, 1e-4, Selection::Fixed(0.), dominance).is_err());
+    }
+
+    #[test_case(Selection::Fixed(5e-3); "fixed selection")]
+    #[test_case(Selection::Mixture(vec![(1., Selection::Fixed(-5e-3)), (1., Selection::Fixed(5e-3))]); "mixture")]
+    fn expected_heterozygosity_random_dominance(selection: Selection) {
+        let dominance = Dominance::Beta {
+            alpha: 0.5,
+            beta: 2.,
+        };
+        let hetero = Heterozygosity::new(1000, 1e-4, selection, dominance).unwrap();
+        let mc: average::Variance = (&hetero)
+            .sample_iter(crate::tests::rng(10))
+            .take(200_000)
             .collect();
-        while variance.error() > error_limit {
-            samples *= 2;
-            // Enhace samples
-            mc_mean_samples = mc_mean_samples
-                .into_par_iter()
-                .update(|mc_mean_sample| mc_mean_sample.merge(&self.mc_mean(samples)))
-                .collect::<Vec<average::Mean>>();
-            // Summarize
-            variance = mc_mean_samples
-                .iter()
-                .map(|mc_mean_sample| mc_mean_sample.mean())
-                .collect();
-        }
-        variance
+
+        let result = hetero.expected_heterozygosity();
+        println!("computed value: {:?}, expected: {:?}", result, mc);
+        assert!((result.integral - mc.mean()).abs() < 4. * mc.error());
     }
-}
 
-impl Distribution<f64> for UnfixedHeterozygosity {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
-        let x = self.sample_frequency(rng);
+    #[test]
+    fn expected_heterozygosity_kacser_burns() {
+        let selection = Selection::Gamma {
+            shape: 0.3,
+            scale: 1e-2,
+        };
+        let dominance = Dominance::KacserBurns { constant: 1e3 };
+        let hetero = Heterozygosity::new(1000, 1e-4, selection, dominance).unwrap();
+        let mc: average::Variance = (&hetero)
+            .sample_iter(crate::tests::rng(11))
+            .take(200_000)
+            .collect();
 
-        2. * x * (1. - x)
+        let result = hetero.expected_heterozygosity();
+        println!("computed value: {:?}, expected: {:?}", result, mc);
+        assert!((result.integral - mc.mean()).abs() < 4. * mc.error());
     }
-}
 
-impl Min<f64> for UnfixedHeterozygosity {
-    /// Returns the minimum value in the domain
-    fn min(&self) -> f64 {
-        0.0
+    #[test]
+    fn sample_gamma_sign() {
+        let hetero = Heterozygosity::new(
+            1000,
+            1e-4,
+            Selection::Gamma {
+                shape: 0.3,
+                scale: 1e-2,
+            },
+            Dominance::Fixed(0.5),
+        )
+        .unwrap();
+        let mut rng = crate::tests::rng(6);
+        assert!((0..1000).all(|_| hetero.sample_selection(&mut rng) <= 0.));
     }
-}
 
-impl Max<f64> for UnfixedHeterozygosity {
-    /// Returns the maximum value in the domain
-    fn max(&self) -> f64 {
-        0.5
+    #[test]
+    fn mc_approx_mean_independent_of_threads() {
+        let hetero = Heterozygosity::new(
+            1000,
+            1e-4,
+            Selection::SkewNormal {
+                location: -1e-3,
+                scale: 1e-3,
+                shape: 0.,
+                bounds: None,
+            },
+            Dominance::Fixed(0.5),
+        )
+        .unwrap();
+        let with_threads = |threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| hetero.mc_approx_mean_seeded(4, 1e-3, 11))
+        };
+        let (single, multiple) = (with_threads(1), with_threads(4));
+        assert_eq!(single.mean(), multiple.mean());
+        assert_eq!(single.error(), multiple.error());
+        assert!(single.error() <= 1e-3);
+    }
+
+    #[test]
+    fn new_truncated_bounds() {
+        let new = |lower, upper| {
+            Heterozygosity::new_truncated(
+                1000,
+                1e-5,
+                Selection::Fixed(0.),
+                Dominance::Fixed(0.5),
+                lower,
+                upper,
+            )
+        };
+        assert!(new(0., 1.).is_ok());
+        assert!(new(1e-3, 0.95).is_ok());
+        assert!(new(-0.1, 1.).is_err());
+        assert!(new(0., 1.1).is_err());
+        assert!(new(0.5, 0.5).is_err());
+        assert!(new(f64::NAN, 1.).is_err());
     }
-}
 
-// #[cfg(test)]
-// mod tests {
-//     use rand::prelude::*;
-//     use super::*;
-// }
+    #[test_case(1000, 1e-4, -1e-3, 1e-3, 2., None, 1000.; "near neutral")]
+    #[test_case(1000, 1e-4, -1e-2, 1e-2, -1., Some((-1e-2, 1e-2)), 100.; "bounded")]
+    #[test_case(500, 1e-4, 0., 1e-1, 0., None, 0.; "strong selection")]
+    fn expected_heterozygosity_mc(
+        population: u64,
+        mutation_rate: f64,
+        location: f64,
+        scale: f64,
+        shape: f64,
+        bounds: Option<(f64, f64)>,
+        rate: f64,
+    ) {
+        let selection = Selection::SkewNormal {
+            location,
+            scale,
+            shape,
+            bounds,
+        };
+        let hetero = Heterozygosity::new(
+            population,
+            mutation_rate,
+            selection,
+            Dominance::Sigmoid { rate },
+        )
+        .unwrap();
+        let mc: average::Variance = (&hetero)
+            .sample_iter(crate::tests::rng(2))
+            .take(200_000)
+            .collect();
+
+        let result = hetero.expected_heterozygosity();
+        println!("computed value: {:?}, expected: {:?}", result, mc);
+        assert!(result.error_estimate < 1e-6 * result.integral);
+        assert!((result.integral - mc.mean()).abs() < 4. * mc.error());
+    }
+}
@@ -1,14 +1,23 @@
 // Traits
 use rand::distributions::Distribution;
 use rand::Rng;
-use statrs::distribution::{Continuous, Univariate};
-use statrs::statistics::{Max, Min}; // , Mean, Variance};
+use statrs::distribution::{CheckedInverseCDF, Continuous, InverseCDF, Univariate};
+use statrs::statistics::{Entropy, Max, Mean, Min, Mode, Skewness, Variance};
 
 // Structs
 use crate::error::{Result, StatsError};
 use rand_distr::StandardNormal;
 use std::f64;
 
+// Functions
+use quadrature::integrate;
+
+// Constants
+use crate::constants::EPS;
+
+use super::helper::{relative_integral, safeguarded_newton};
+use super::sample_window;
+
 /// Implements the [skew Normal](https://en.wikipedia.org/wiki/Skew_normal_distribution)
 /// distribution.
 ///
@@ -57,6 +66,372 @@ impl SkewNormal {
             })
         }
     }
+
+    /// Constructs a new skew normal distribution with the given mean, standard deviation and skewness.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `mean` or `std_dev` are not finite, if `std_dev <= 0.0`,
+    /// or if `|skewness|` is not below the largest skewness of a skew normal distribution, about 0.9953.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::SkewNormal;
+    /// use statrs::statistics::{Mean, Skewness, Variance};
+    ///
+    /// let sn = SkewNormal::from_moments(-0.01, 0.02, -0.5).unwrap();
+    /// assert!((sn.mean() + 0.01).abs() < 1e-12);
+    /// assert!((sn.std_dev() - 0.02).abs() < 1e-12);
+    /// assert!((sn.skewness() + 0.5).abs() < 1e-12);
+    ///
+    /// assert!(SkewNormal::from_moments(0., 1., 1.).is_err());
+    /// ```
+    pub fn from_moments(mean: f64, std_dev: f64, skewness: f64) -> Result<Self> {
+        if !mean.is_finite() || std_dev <= 0. || !std_dev.is_finite() || skewness.is_nan() {
+            return Err(StatsError::BadParams);
+        }
+        // Standardized mean `b delta`, from `skewness = (4 - pi) / 2 * (b delta)^3 / (1 - (b delta)^2)^(3/2)`
+        let ratio = (2. * skewness.abs() / (4. - f64::consts::PI)).cbrt();
+        let standardized_mean = skewness.signum() * ratio / (1. + ratio * ratio).sqrt();
+        let delta = standardized_mean / f64::consts::FRAC_2_PI.sqrt();
+        if delta.abs() >= 1. {
+            return Err(StatsError::BadParams);
+        }
+        let scale = std_dev / (1. - standardized_mean * standardized_mean).sqrt();
+        SkewNormal::new(
+            mean - scale * standardized_mean,
+            scale,
+            delta / (1. - delta * delta).sqrt(),
+        )
+    }
+
+    /// Mean of the standardized variable `(x - location) / scale`, `sqrt(2 / pi) * delta`,
+    /// where `delta = shape / sqrt(1 + shape^2)`.
+    fn standardized_mean(&self) -> f64 {
+        f64::consts::FRAC_2_PI.sqrt() * self.shape / (1. + self.shape * self.shape).sqrt()
+    }
+
+    /// Fits a skew normal distribution to `data` by maximum likelihood.
+    ///
+    /// # Algorithm
+    ///
+    /// Starting from the method of moments, with the sample skewness clamped to `[-0.9, 0.9]`,
+    /// the log-likelihood is maximized by Newton's method with Levenberg–Marquardt damping
+    /// until the Newton decrement is negligible relative to the number of values,
+    /// or until no damped step improves the log-likelihood at a point with positive definite information.
+    /// Standard errors come from the observed information at the maximum.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BadParams` if `data` has fewer than three values, a non-finite value or no spread,
+    /// and `ComputationFailedToConverge` if the maximization does not converge.
+    /// The latter can happen when the data is so skewed that the likelihood grows without bound in the shape.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::distributions::Distribution;
+    /// use sandpiper::SkewNormal;
+    ///
+    /// let data: Vec<f64> = SkewNormal::new(-0.01, 0.02, -3.)
+    ///     .unwrap()
+    ///     .sample_iter(rand::thread_rng())
+    ///     .take(1000)
+    ///     .collect();
+    /// let fit = SkewNormal::fit(&data).unwrap();
+    /// println!("{:?} with standard errors {:?}", fit.distribution(), fit.standard_errors());
+    /// ```
+    pub fn fit(data: &[f64]) -> Result<SkewNormalFit> {
+        if data.len() < 3 || data.iter().any(|x| !x.is_finite()) {
+            return Err(StatsError::BadParams);
+        }
+        let n = data.len() as f64;
+        let mean = data.iter().sum::<f64>() / n;
+        let central_moment = |k: i32| data.iter().map(|x| (x - mean).powi(k)).sum::<f64>() / n;
+        let variance = central_moment(2);
+        if variance <= 0. {
+            return Err(StatsError::BadParams);
+        }
+        let skewness = (central_moment(3) / variance.powf(1.5)).clamp(-0.9, 0.9);
+        let start = SkewNormal::from_moments(mean, variance.sqrt(), skewness)?;
+
+        let mut parameters = [start.location, start.scale, start.shape];
+        let mut damping = INITIAL_DAMPING;
+        let converged = |parameters: [f64; 3], factor: &[[f64; 3]; 3], ln_likelihood: f64| {
+            let variance = |i: usize| {
+                let mut unit = [0.; 3];
+                unit[i] = 1.;
+                cholesky_solve(factor, unit)[i]
+            };
+            let standard_errors = [variance(0).sqrt(), variance(1).sqrt(), variance(2).sqrt()];
+            let (location, scale, shape) = (parameters[0], parameters[1], parameters[2]);
+            Ok(SkewNormalFit {
+                distribution: SkewNormal::new(location, scale, shape)?,
+                standard_errors,
+                ln_likelihood,
+            })
+        };
+        for _ in 0..MAX_FIT_ITERATIONS {
+            let (ln_likelihood, gradient, hessian) = ln_likelihood_derivatives(parameters, data);
+            let information = negated(hessian);
+            let factor = cholesky(information);
+            if let Some(factor) = factor {
+                let newton = cholesky_solve(&factor, gradient);
+                let decrement: f64 = (0..3).map(|i| gradient[i] * newton[i]).sum();
+                if decrement < FIT_TOLERANCE * n {
+                    return converged(parameters, &factor, ln_likelihood);
+                }
+            }
+            // Damped step, increasing the damping until the log-likelihood improves
+            loop {
+                let mut damped = information;
+                for (i, row) in damped.iter_mut().enumerate() {
+                    row[i] += damping * information[i][i].abs().max(f64::MIN_POSITIVE);
+                }
+                if let Some(factor) = cholesky(damped) {
+                    let step = cholesky_solve(&factor, gradient);
+                    let candidate = [
+                        parameters[0] + step[0],
+                        parameters[1] + step[1],
+                        parameters[2] + step[2],
+                    ];
+                    if candidate[1] > 0. && ln_likelihood_sum(candidate, data) > ln_likelihood {
+                        parameters = candidate;
+                        damping = (damping * 0.1).max(MIN_DAMPING);
+                        break;
+                    }
+                }
+                damping *= 10.;
+                if damping > MAX_DAMPING {
+                    // No step improves the log-likelihood beyond its rounding error,
+                    // which is a maximum if the information is positive definite
+                    return match factor {
+                        Some(factor) => converged(parameters, &factor, ln_likelihood),
+                        None => Err(StatsError::ComputationFailedToConverge),
+                    };
+                }
+            }
+        }
+        Err(StatsError::ComputationFailedToConverge)
+    }
+}
+
+impl SkewNormal {
+    /// Returns the probability of `[lower, upper]`.
+    ///
+    /// # Remarks
+    ///
+    /// It is computed by numerical quadrature of the density, accurately in relative terms,
+    /// so that intervals in the tails have a positive probability as long as the density does not underflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::SkewNormal;
+    ///
+    /// let sn = SkewNormal::new(0.0, 1.0, 3.0).unwrap();
+    /// // P(X > 0) = 1/2 + arctan(shape) / pi
+    /// let expected = 0.5 + 3.0_f64.atan() / std::f64::consts::PI;
+    /// assert!((sn.probability_between(0., f64::INFINITY) - expected).abs() < 1e-10);
+    /// assert!(sn.probability_between(-10., -9.) > 0.);
+    /// ```
+    pub fn probability_between(&self, lower: f64, upper: f64) -> f64 {
+        let (lower, upper) = self.standardized_range(lower, upper);
+        if lower >= upper {
+            return 0.;
+        }
+        self.standardized_mass(lower, upper)
+    }
+
+    /// Samples conditioned on `lower <= x <= upper`.
+    ///
+    /// # Remarks
+    ///
+    /// Proposals outside the interval are rejected up to `MAX_WINDOW_REJECTIONS` times,
+    /// after which the sample is obtained by inverse transform within the interval,
+    /// so that intervals with little probability do not stall sampling.
+    /// If the probability of the interval underflows, the bound with larger density is returned.
+    ///
+    /// # Examples
+    ///
+    /// Sampling from a tail.
+    /// ```
+    /// use sandpiper::SkewNormal;
+    ///
+    /// let sn = SkewNormal::new(0.0, 1.0, 0.0).unwrap();
+    /// let x = sn.sample_between(8., 9., &mut rand::thread_rng());
+    /// assert!(8. <= x && x <= 9.);
+    /// ```
+    pub fn sample_between<R: Rng + ?Sized>(&self, lower: f64, upper: f64, rng: &mut R) -> f64 {
+        sample_window(
+            rng,
+            |rng| self.sample(rng),
+            |&x| lower <= x && x <= upper,
+            |uniform| {
+                let (z_lower, z_upper) = self.standardized_range(lower, upper);
+                let mass = if z_lower < z_upper {
+                    self.standardized_mass(z_lower, z_upper)
+                } else {
+                    0.
+                };
+                if mass <= 0. {
+                    return if self.ln_pdf(lower) >= self.ln_pdf(upper) {
+                        lower
+                    } else {
+                        upper
+                    };
+                }
+                let z = self.standardized_mass_inverse(z_lower, z_upper, uniform * mass);
+                (self.location + self.scale * z).max(lower).min(upper)
+            },
+        )
+    }
+
+    /// Returns the quantiles of probability `tail` and `1 - tail`.
+    ///
+    /// The upper quantile is the lower quantile of the reflected distribution,
+    /// so that both tails are found with relative accuracy.
+    pub(crate) fn central_interval(&self, tail: f64) -> (f64, f64) {
+        let reflected = SkewNormal {
+            location: -self.location,
+            shape: -self.shape,
+            ..*self
+        };
+        (self.inverse_cdf(tail), -reflected.inverse_cdf(tail))
+    }
+
+    /// Distribution function of the standardized variable `(x - location) / scale` at `z`.
+    ///
+    /// It is computed through Owen's T function, `Phi(z) - 2 T(z, shape)`,
+    /// unless most digits cancel, in which case the lower tail is integrated numerically.
+    fn standardized_cdf(&self, z: f64) -> f64 {
+        if z <= -STANDARDIZED_RANGE {
+            return 0.;
+        } else if z >= STANDARDIZED_RANGE {
+            return 1.;
+        }
+        let normal = crate::Normal::new(0., 1.).unwrap();
+        let phi = normal.cdf(z);
+        let value = phi - 2. * owens_t(z, self.shape);
+        if value < CANCELLATION * phi {
+            self.standardized_mass(-STANDARDIZED_RANGE, z)
+        } else {
+            value.min(1.)
+        }
+    }
+
+    /// Standardized interval `(x - location) / scale` for `x` in `[lower, upper]`,
+    /// restricted to where the density does not underflow.
+    fn standardized_range(&self, lower: f64, upper: f64) -> (f64, f64) {
+        (
+            ((lower - self.location) / self.scale).max(-STANDARDIZED_RANGE),
+            ((upper - self.location) / self.scale).min(STANDARDIZED_RANGE),
+        )
+    }
+
+    /// Density of the standardized variable `(x - location) / scale` at `z`.
+    fn standardized_pdf(&self, z: f64) -> f64 {
+        pdf_unchecked(z, 0., 1., self.shape)
+    }
+
+    /// Splits `[lower, upper]` at zero and at geometric distances `10^k / |shape|` from it,
+    /// where the standardized density changes abruptly for large shapes.
+    fn standardized_breakpoints(&self, lower: f64, upper: f64) -> Vec<f64> {
+        let mut breakpoints = vec![lower, upper];
+        let mut distance = if self.shape == 0. {
+            STANDARDIZED_RANGE
+        } else {
+            self.shape.abs().recip()
+        };
+        if lower < 0. && 0. < upper {
+            breakpoints.push(0.);
+        }
+        while distance < STANDARDIZED_RANGE {
+            for &point in &[-distance, distance] {
+                if lower < point && point < upper {
+                    breakpoints.push(point);
+                }
+            }
+            distance *= 10.;
+        }
+        breakpoints.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        breakpoints
+    }
+
+    /// Probability of the standardized variable being in `[lower, upper]`, accurately in relative terms.
+    ///
+    /// The range is split by `standardized_breakpoints`.
+    fn standardized_mass(&self, lower: f64, upper: f64) -> f64 {
+        let breakpoints = self.standardized_breakpoints(lower, upper);
+        relative_integral(|z| self.standardized_pdf(z), &breakpoints).integral
+    }
+
+    /// Finds `z` in `[lower, upper]` such that `standardized_mass(lower, z) == mass`.
+    ///
+    /// # Algorithm
+    ///
+    /// Newton's method, safeguarded by bisection with `safeguarded_newton`.
+    /// The mass up to the current point is updated by integrating between consecutive iterates.
+    fn standardized_mass_inverse(&self, lower: f64, upper: f64, mass: f64) -> f64 {
+        let mut previous = lower;
+        let mut mass_below = 0.;
+        let value_and_derivative = |z: f64| {
+            if z != previous {
+                let increment = self.standardized_mass(previous.min(z), previous.max(z));
+                mass_below += if z > previous { increment } else { -increment };
+                previous = z;
+            }
+            (mass_below - mass, self.standardized_pdf(z))
+        };
+        safeguarded_newton(value_and_derivative, (lower, upper), lower)
+    }
+}
+
+/// Standardized values beyond which the density underflows, since it is bounded by twice the standard normal density.
+const STANDARDIZED_RANGE: f64 = 40.;
+
+/// Ratio between the distribution function and the normal one below which
+/// the cancellation in `Phi(z) - 2 T(z, shape)` is too large.
+const CANCELLATION: f64 = 1e-3;
+
+/// Maximum number of Newton iterations in `SkewNormal::fit`.
+const MAX_FIT_ITERATIONS: usize = 200;
+
+/// Newton decrement per observation, about twice the distance to the maximum log-likelihood,
+/// at which `SkewNormal::fit` stops.
+const FIT_TOLERANCE: f64 = 1e-12;
+
+/// Levenberg–Marquardt damping, relative to the diagonal of the observed information.
+const INITIAL_DAMPING: f64 = 1e-3;
+const MIN_DAMPING: f64 = 1e-12;
+const MAX_DAMPING: f64 = 1e16;
+
+/// Result of fitting a skew normal distribution by maximum likelihood with `SkewNormal::fit`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SkewNormalFit {
+    distribution: SkewNormal,
+    standard_errors: [f64; 3],
+    ln_likelihood: f64,
+}
+
+impl SkewNormalFit {
+    /// Maximum likelihood estimate.
+    pub fn distribution(&self) -> SkewNormal {
+        self.distribution
+    }
+
+    /// Standard errors of the location, scale and shape, from the inverse of the observed information.
+    ///
+    /// They are large when the shape is close to zero, where the information is nearly singular.
+    pub fn standard_errors(&self) -> [f64; 3] {
+        self.standard_errors
+    }
+
+    /// Log-likelihood of the data at the estimate.
+    pub fn ln_likelihood(&self) -> f64 {
+        self.ln_likelihood
+    }
 }
 
 impl Distribution<f64> for SkewNormal {
@@ -93,6 +468,190 @@ impl Max<f64> for SkewNormal {
     }
 }
 
+impl Univariate<f64, f64> for SkewNormal {
+    /// Calculates the cumulative distribution function for the
+    /// skew normal distribution at `x`.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// Phi( (x - xi) / omega ) - 2 T( (x - xi) / omega, alpha )
+    /// ```
+    ///
+    /// where `xi` is the location, `omega` is the scale, `alpha` is the
+    /// shape, `Phi` is the distribution of a standard normal variable and `T` is Owen's T function.
+    ///
+    /// # Remarks
+    ///
+    /// Where the formula loses most of its digits, in the light lower tail,
+    /// the density is integrated numerically instead, so that the result is accurate in relative terms.
+    ///
+    /// # Examples
+    ///
+    /// Fraction of mutations with selection below -1e-3.
+    /// ```
+    /// use sandpiper::SkewNormal;
+    /// use statrs::distribution::Univariate;
+    ///
+    /// let sn = SkewNormal::new(0.0, 1e-3, 3.0).unwrap();
+    /// let fraction = sn.cdf(-1e-3);
+    /// assert!(0. < fraction && fraction < 0.01);
+    /// ```
+    fn cdf(&self, x: f64) -> f64 {
+        self.standardized_cdf((x - self.location) / self.scale)
+    }
+}
+
+impl InverseCDF<f64> for SkewNormal {
+    /// Calculates the inverse cumulative distribution function for the
+    /// skew normal distribution at `x`.
+    ///
+    /// # Panics
+    ///
+    /// If `x < 0.0` or `x > 1.0`
+    ///
+    /// # Remarks
+    ///
+    /// It is computed by Newton's method, safeguarded by bisection, over the standardized variable.
+    fn inverse_cdf(&self, x: f64) -> f64 {
+        self.checked_inverse_cdf(x).unwrap()
+    }
+}
+
+impl CheckedInverseCDF<f64> for SkewNormal {
+    /// Calculates the inverse cumulative distribution function for the
+    /// skew normal distribution at `x`.
+    ///
+    /// # Errors
+    ///
+    /// If `x < 0.0` or `x > 1.0`
+    fn checked_inverse_cdf(&self, x: f64) -> Result<f64> {
+        if !(0.0..=1.0).contains(&x) {
+            return Err(StatsError::ArgIntervalIncl("x", 0.0, 1.0));
+        }
+        if x == 0. {
+            return Ok(f64::NEG_INFINITY);
+        } else if x == 1. {
+            return Ok(f64::INFINITY);
+        }
+        let value_and_derivative =
+            |z: f64| (self.standardized_cdf(z) - x, self.standardized_pdf(z));
+        let current = safeguarded_newton(
+            value_and_derivative,
+            (-STANDARDIZED_RANGE, STANDARDIZED_RANGE),
+            0.,
+        );
+        Ok(self.location + self.scale * current)
+    }
+}
+
+impl Mean<f64> for SkewNormal {
+    /// Returns the mean of the skew normal distribution.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// xi + omega * delta * sqrt(2 / π)
+    /// ```
+    ///
+    /// where `xi` is the location, `omega` is the scale and `delta = alpha / sqrt(1 + alpha^2)`
+    /// for the shape `alpha`.
+    fn mean(&self) -> f64 {
+        self.location + self.scale * self.standardized_mean()
+    }
+}
+
+impl Variance<f64> for SkewNormal {
+    /// Returns the variance of the skew normal distribution.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// omega^2 * (1 - 2 delta^2 / π)
+    /// ```
+    ///
+    /// where `omega` is the scale and `delta = alpha / sqrt(1 + alpha^2)` for the shape `alpha`.
+    fn variance(&self) -> f64 {
+        let standardized_mean = self.standardized_mean();
+        self.scale * self.scale * (1. - standardized_mean * standardized_mean)
+    }
+
+    /// Returns the standard deviation of the skew normal distribution.
+    fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+}
+
+impl Skewness<f64> for SkewNormal {
+    /// Returns the skewness of the skew normal distribution.
+    ///
+    /// # Formula
+    ///
+    /// ```ignore
+    /// (4 - π) / 2 * (delta * sqrt(2 / π))^3 / (1 - 2 delta^2 / π)^(3/2)
+    /// ```
+    ///
+    /// where `delta = alpha / sqrt(1 + alpha^2)` for the shape `alpha`.
+    fn skewness(&self) -> f64 {
+        let standardized_mean = self.standardized_mean();
+        (4. - f64::consts::PI) / 2. * standardized_mean.powi(3)
+            / (1. - standardized_mean * standardized_mean).powf(1.5)
+    }
+}
+
+impl Mode<f64> for SkewNormal {
+    /// Returns the mode of the skew normal distribution.
+    ///
+    /// # Remarks
+    ///
+    /// There is no closed form. Since the density is log-concave, the standardized mode is the root of
+    /// `-z + alpha phi(alpha z) / Phi(alpha z)`, found by bisection between zero and `sqrt(2 / π) * sign(alpha)`.
+    fn mode(&self) -> f64 {
+        let normal = crate::Normal::new(0., 1.).unwrap();
+        let slope =
+            |z: f64| -z + self.shape * normal.pdf(self.shape * z) / normal.cdf(self.shape * z);
+        let bound = f64::consts::FRAC_2_PI.sqrt() * self.shape.signum();
+        let (mut lower, mut upper) = (bound.min(0.), bound.max(0.));
+        while upper - lower > f64::EPSILON * upper.abs().max(lower.abs()) {
+            let middle = 0.5 * (lower + upper);
+            if middle <= lower || middle >= upper {
+                break;
+            }
+            if slope(middle) > 0. {
+                lower = middle;
+            } else {
+                upper = middle;
+            }
+        }
+        self.location + self.scale * 0.5 * (lower + upper)
+    }
+}
+
+impl Entropy<f64> for SkewNormal {
+    /// Returns the differential entropy of the skew normal distribution.
+    ///
+    /// # Remarks
+    ///
+    /// There is no closed form. It is computed as `ln(omega)` plus the entropy of the standardized variable,
+    /// by numerical quadrature of `-f ln f` over the pieces of `standardized_breakpoints`.
+    fn entropy(&self) -> f64 {
+        let breakpoints = self.standardized_breakpoints(-STANDARDIZED_RANGE, STANDARDIZED_RANGE);
+        let integrand = |z: f64| {
+            let density = self.standardized_pdf(z);
+            if density > 0. {
+                -density * density.ln()
+            } else {
+                0.
+            }
+        };
+        let standardized_entropy: f64 = breakpoints
+            .windows(2)
+            .map(|window| integrate(integrand, window[0], window[1], EPS).integral)
+            .sum();
+        self.scale.ln() + standardized_entropy
+    }
+}
+
 impl Continuous<f64, f64> for SkewNormal {
     /// Calculates the probability density function for the
     /// skew normal distribution at `x`.
@@ -116,10 +675,12 @@ impl Continuous<f64, f64> for SkewNormal {
     /// # Formula
     ///
     /// ```ignore
-    /// ln((1 / sqrt(2σ^2 * π)) * e^(-(x - μ)^2 / 2σ^2))
+    /// ln(2) - ln(omega) + ln(phi( (x - xi) / omega )) + ln(Phi( alpha (x - xi) / omega ))
     /// ```
     ///
-    /// where `μ` is the mean and `σ` is the standard deviation
+    /// where `xi` is the location, `omega` is the scale, `alpha` is the
+    /// shape and `phi` and `Phi` are the density and distribution
+    /// of a standard normal variable.
     fn ln_pdf(&self, x: f64) -> f64 {
         ln_pdf_unchecked(x, self.location, self.scale, self.shape)
     }
@@ -133,14 +694,115 @@ pub fn pdf_unchecked(x: f64, location: f64, scale: f64, shape: f64) -> f64 {
     2. / scale * normal.pdf(d) * normal.cdf(shape * d)
 }
 
-/// performs an unchecked log(pdf) calculation for a normal distribution
-/// with the given mean and standard deviation at x
+/// performs an unchecked log(pdf) calculation for a skew normal distribution
+/// with the given location, scale and shape at x
 pub fn ln_pdf_unchecked(x: f64, location: f64, scale: f64, shape: f64) -> f64 {
     let d = (x - location) / scale;
-    let normal = crate::Normal::new(0., 1.).unwrap();
+    f64::consts::LN_2 - scale.ln() - 0.5 * d * d - statrs::consts::LN_SQRT_2PI
+        + ln_normal_cdf(shape * d)
+}
+
+/// Logarithm of the standard normal distribution function,
+/// with its asymptotic expansion where the distribution function underflows.
+fn ln_normal_cdf(x: f64) -> f64 {
+    if x > -ASYMPTOTIC_NORMAL_TAIL {
+        (0.5 * statrs::function::erf::erfc(-x / f64::consts::SQRT_2)).ln()
+    } else {
+        let inverse_square = (x * x).recip();
+        -0.5 * x * x - (-x).ln() - statrs::consts::LN_SQRT_2PI
+            + (1. - inverse_square * (1. - 3. * inverse_square * (1. - 5. * inverse_square))).ln()
+    }
+}
+
+/// Standardized values beyond which `ln_normal_cdf` uses the asymptotic expansion, accurate to about `1e-10` there.
+const ASYMPTOTIC_NORMAL_TAIL: f64 = 30.;
+
+/// Log-likelihood of `data` for the parameters `[location, scale, shape]`.
+fn ln_likelihood_sum(parameters: [f64; 3], data: &[f64]) -> f64 {
+    let [location, scale, shape] = parameters;
+    data.iter()
+        .map(|&x| ln_pdf_unchecked(x, location, scale, shape))
+        .sum()
+}
+
+/// Log-likelihood of `data` with its gradient and Hessian with respect to `[location, scale, shape]`.
+///
+/// With `z = (x - location) / scale`, `t = shape z` and the inverse Mills ratio `w = phi(t) / Phi(t)`,
+/// whose derivative is `-w (t + w)`, the log-likelihood of each value is `-ln(scale) - z^2 / 2 + ln(Phi(t))`
+/// up to a constant.
+fn ln_likelihood_derivatives(parameters: [f64; 3], data: &[f64]) -> (f64, [f64; 3], [[f64; 3]; 3]) {
+    let [location, scale, shape] = parameters;
+    let mut ln_likelihood = 0.;
+    let mut gradient = [0.; 3];
+    let mut hessian = [[0.; 3]; 3];
+    for &x in data {
+        let z = (x - location) / scale;
+        let t = shape * z;
+        let ln_cdf = ln_normal_cdf(t);
+        let w = (-0.5 * t * t - statrs::consts::LN_SQRT_2PI - ln_cdf).exp();
+        let dw = -w * (t + w);
+
+        ln_likelihood +=
+            f64::consts::LN_2 - scale.ln() - 0.5 * z * z - statrs::consts::LN_SQRT_2PI + ln_cdf;
+        gradient[0] += (z - shape * w) / scale;
+        gradient[1] += (z * z - t * w - 1.) / scale;
+        gradient[2] += z * w;
+        hessian[0][0] += (shape * shape * dw - 1.) / (scale * scale);
+        hessian[0][1] += (shape * w + shape * shape * z * dw - 2. * z) / (scale * scale);
+        hessian[0][2] -= (w + t * dw) / scale;
+        hessian[1][1] += (1. - 3. * z * z + 2. * t * w + t * t * dw) / (scale * scale);
+        hessian[1][2] -= z * (w + t * dw) / scale;
+        hessian[2][2] += z * z * dw;
+    }
+    hessian[1][0] = hessian[0][1];
+    hessian[2][0] = hessian[0][2];
+    hessian[2][1] = hessian[1][2];
+    (ln_likelihood, gradient, hessian)
+}
+
+fn negated(matrix: [[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut negated = matrix;
+    for row in negated.iter_mut() {
+        for value in row.iter_mut() {
+            *value = -*value;
+        }
+    }
+    negated
+}
 
-    2.0_f64.ln() - scale.ln() + (-0.5 * d * d) - statrs::consts::LN_SQRT_2PI - scale.ln()
-        + normal.cdf(shape * d).ln()
+/// Lower triangular Cholesky factor, or `None` if `matrix` is not positive definite.
+fn cholesky(matrix: [[f64; 3]; 3]) -> Option<[[f64; 3]; 3]> {
+    let mut factor = [[0.; 3]; 3];
+    for i in 0..3 {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| factor[i][k] * factor[j][k]).sum();
+            if i == j {
+                let pivot = matrix[i][i] - sum;
+                if pivot.is_nan() || pivot <= 0. {
+                    return None;
+                }
+                factor[i][i] = pivot.sqrt();
+            } else {
+                factor[i][j] = (matrix[i][j] - sum) / factor[j][j];
+            }
+        }
+    }
+    Some(factor)
+}
+
+/// Solves `L L^T x = rhs` for the Cholesky factor `L`.
+fn cholesky_solve(factor: &[[f64; 3]; 3], rhs: [f64; 3]) -> [f64; 3] {
+    let mut y = [0.; 3];
+    for i in 0..3 {
+        let sum: f64 = (0..i).map(|k| factor[i][k] * y[k]).sum();
+        y[i] = (rhs[i] - sum) / factor[i][i];
+    }
+    let mut x = [0.; 3];
+    for i in (0..3).rev() {
+        let sum: f64 = (i + 1..3).map(|k| factor[k][i] * x[k]).sum();
+        x[i] = (y[i] - sum) / factor[i][i];
+    }
+    x
 }
 
 /// Draws two samples from a standard normal distribution using the
@@ -166,17 +828,41 @@ pub fn sample_unchecked<R: Rng + ?Sized>(r: &mut R, location: f64, scale: f64, s
     }
 }
 
+/// Owen's T function `T(h, a) = 1 / (2 pi) \int_0^a exp(-h^2 (1 + x^2) / 2) / (1 + x^2) dx`.
+///
+/// For `|a| <= 1` the integral is computed by numerical quadrature, after factoring out `exp(-h^2 / 2)`.
+/// Otherwise, it uses the identity
+/// `T(h, a) = (Phi(h) Q(ah) + Phi(ah) Q(h)) / 2 - T(ah, 1 / a)` for `h, a >= 0`,
+/// where `Q = 1 - Phi` is computed without cancellation.
+fn owens_t(h: f64, a: f64) -> f64 {
+    let (h, sign, a) = (h.abs(), a.signum(), a.abs());
+    if a == 0. {
+        return 0.;
+    }
+    let value = if a <= 1. {
+        let integrand = |x: f64| (-0.5 * h * h * x * x).exp() / (1. + x * x);
+        (-0.5 * h * h).exp() * integrate(integrand, 0., a, EPS).integral / (2. * f64::consts::PI)
+    } else {
+        let normal = crate::Normal::new(0., 1.).unwrap();
+        let ah = a * h;
+        0.5 * (normal.cdf(h) * normal.cdf(-ah) + normal.cdf(ah) * normal.cdf(-h))
+            - owens_t(ah, a.recip())
+    };
+    sign * value
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rayon::prelude::*;
     use test_case::test_case;
 
-    #[test_case(0., 1., 0., 0.0; "neutral shape")]
-    #[test_case(0., 1., -1., -0.557; "negative shape")]
-    #[test_case(0., 1., 1., 0.569; "positive shape")]
-    #[test_case(0., 10., -1., -5.57; "spreaded negative shape")]
-    #[test_case(0., 10., 1., 5.69; "spreaded positive shape")]
-    fn mean(location: f64, scale: f64, shape: f64, expected: f64) {
+    #[test_case(0., 1., 0.; "neutral shape")]
+    #[test_case(0., 1., -1.; "negative shape")]
+    #[test_case(0., 1., 1.; "positive shape")]
+    #[test_case(0., 10., -1.; "spreaded negative shape")]
+    #[test_case(0., 10., 1.; "spreaded positive shape")]
+    fn sample_mean(location: f64, scale: f64, shape: f64) {
         let skew_normal = SkewNormal::new(location, scale, shape).unwrap();
         let samples = 100_000;
 
@@ -187,6 +873,278 @@ mod tests {
             / (samples as f64);
 
         println!("computed value: {:?}", result);
-        assert!((expected - result).abs() < 1e-2);
+        let standard_error = skew_normal.std_dev() / (samples as f64).sqrt();
+        assert!((skew_normal.mean() - result).abs() < 4. * standard_error);
+    }
+
+    #[test_case(0., 1., 0., 0.; "neutral shape")]
+    #[test_case(0., 1., -1., -1. / f64::consts::PI.sqrt(); "negative shape")]
+    #[test_case(2., 10., 1., 2. + 10. / f64::consts::PI.sqrt(); "spreaded positive shape")]
+    fn mean(location: f64, scale: f64, shape: f64, expected: f64) {
+        // The maximum of two standard normal variables has mean 1 / sqrt(pi)
+        let skew_normal = SkewNormal::new(location, scale, shape).unwrap();
+        assert!((skew_normal.mean() - expected).abs() < 1e-14 * scale);
+    }
+
+    #[test_case(0., 1., 0.; "normal")]
+    #[test_case(1., 2., 4.; "positive shape")]
+    #[test_case(-0.01, 1e-3, -20.; "narrow")]
+    fn moments_quadrature(location: f64, scale: f64, shape: f64) {
+        let skew_normal = SkewNormal::new(location, scale, shape).unwrap();
+        let moment = |k: i32| {
+            let breakpoints =
+                skew_normal.standardized_breakpoints(-STANDARDIZED_RANGE, STANDARDIZED_RANGE);
+            breakpoints
+                .windows(2)
+                .map(|window| {
+                    integrate(
+                        |z| {
+                            (z - skew_normal.standardized_mean()).powi(k)
+                                * skew_normal.standardized_pdf(z)
+                        },
+                        window[0],
+                        window[1],
+                        EPS,
+                    )
+                    .integral
+                })
+                .sum::<f64>()
+        };
+        let variance = scale * scale * moment(2);
+        let skewness = moment(3) / moment(2).powf(1.5);
+
+        assert!((skew_normal.variance() - variance).abs() < 1e-10 * variance);
+        assert!((skew_normal.skewness() - skewness).abs() < 1e-10);
+    }
+
+    #[test_case(0.; "normal")]
+    #[test_case(3.; "positive shape")]
+    #[test_case(-30.; "large negative shape")]
+    fn mode(shape: f64) {
+        let skew_normal = SkewNormal::new(1., 2., shape).unwrap();
+        let mode = skew_normal.mode();
+        let step = 1e-6;
+        assert!(skew_normal.ln_pdf(mode) >= skew_normal.ln_pdf(mode - step));
+        assert!(skew_normal.ln_pdf(mode) >= skew_normal.ln_pdf(mode + step));
+    }
+
+    #[test]
+    fn entropy_normal() {
+        let skew_normal = SkewNormal::new(0., 3., 0.).unwrap();
+        let expected = 0.5 * (2. * f64::consts::PI * f64::consts::E * 9.).ln();
+        assert!((skew_normal.entropy() - expected).abs() < 1e-12);
+    }
+
+    #[test_case(-0.01, 0.02, -0.5; "negative skewness")]
+    #[test_case(3., 1., 0.99; "nearly largest skewness")]
+    #[test_case(0., 1., 0.; "normal")]
+    fn from_moments(mean: f64, std_dev: f64, skewness: f64) {
+        let skew_normal = SkewNormal::from_moments(mean, std_dev, skewness).unwrap();
+        assert!((skew_normal.mean() - mean).abs() < 1e-12 * std_dev);
+        assert!((skew_normal.std_dev() - std_dev).abs() < 1e-12 * std_dev);
+        assert!((skew_normal.skewness() - skewness).abs() < 1e-10);
+    }
+
+    #[test_case(-1., 2.; "central")]
+    #[test_case(8., 9.; "upper tail")]
+    #[test_case(-30., -20.; "far lower tail")]
+    fn probability_between_normal(lower: f64, upper: f64) {
+        let skew_normal = SkewNormal::new(0., 1., 0.).unwrap();
+        let upper_tail = |x: f64| 0.5 * statrs::function::erf::erfc(x / f64::consts::SQRT_2);
+        let expected = if lower < 0. {
+            upper_tail(-upper) - upper_tail(-lower)
+        } else {
+            upper_tail(lower) - upper_tail(upper)
+        };
+
+        let result = skew_normal.probability_between(lower, upper);
+        println!("computed value: {:?}, expected: {:?}", result, expected);
+        assert!((result - expected).abs() < 1e-10 * expected);
+    }
+
+    #[test_case(0., 1., -1e6; "extreme shape")]
+    #[test_case(-0.05, 1e-5, 30.; "narrow scale")]
+    fn probability_between_total(location: f64, scale: f64, shape: f64) {
+        let skew_normal = SkewNormal::new(location, scale, shape).unwrap();
+
+        let result = skew_normal.probability_between(f64::NEG_INFINITY, f64::INFINITY);
+        assert!((result - 1.).abs() < 1e-10);
+    }
+
+    #[test_case(0., 1., 0.; "standard normal")]
+    #[test_case(-1e-3, 1e-5, -5.; "narrow skewed")]
+    fn central_interval(location: f64, scale: f64, shape: f64) {
+        let sn = SkewNormal::new(location, scale, shape).unwrap();
+        let tail = 1e-6;
+        let (lower, upper) = sn.central_interval(tail);
+        let below = sn.probability_between(f64::NEG_INFINITY, lower);
+        let above = sn.probability_between(upper, f64::INFINITY);
+        assert!((below - tail).abs() < 1e-5 * tail);
+        assert!((above - tail).abs() < 1e-5 * tail);
+    }
+
+    #[test_case(0.; "normal")]
+    #[test_case(3.; "positive shape")]
+    #[test_case(-0.5; "negative shape")]
+    fn cdf_at_location(shape: f64) {
+        // P(X <= location) = 1/2 - arctan(shape) / pi
+        let skew_normal = SkewNormal::new(1., 2., shape).unwrap();
+        let expected = 0.5 - shape.atan() / f64::consts::PI;
+        assert!((skew_normal.cdf(1.) - expected).abs() < 1e-14);
+    }
+
+    #[test_case(0., 1., 0., -3.; "normal")]
+    #[test_case(0., 1., 5., -0.5; "lower tail")]
+    #[test_case(0., 1., 5., 2.; "upper half")]
+    #[test_case(-0.01, 1e-3, -20., -0.0105; "narrow")]
+    #[test_case(0., 1., 2., -4.; "far lower tail")]
+    fn cdf_quadrature(location: f64, scale: f64, shape: f64, x: f64) {
+        let skew_normal = SkewNormal::new(location, scale, shape).unwrap();
+        let expected = skew_normal.probability_between(f64::NEG_INFINITY, x);
+        let result = skew_normal.cdf(x);
+        assert!((result - expected).abs() < 1e-9 * expected);
+    }
+
+    #[test_case(0., 1., 5.; "positive shape")]
+    #[test_case(-0.01, 1e-3, -20.; "narrow")]
+    fn inverse_cdf(location: f64, scale: f64, shape: f64) {
+        let skew_normal = SkewNormal::new(location, scale, shape).unwrap();
+        for &p in &[1e-12, 0.01, 0.5, 0.99] {
+            let x = skew_normal.inverse_cdf(p);
+            assert!((skew_normal.cdf(x) - p).abs() < 1e-9 * p);
+        }
+    }
+
+    #[test]
+    fn sample_between_tail() {
+        let skew_normal = SkewNormal::new(0., 1., 0.).unwrap();
+        let (lower, upper) = (8., 9.);
+        let mut rng = crate::tests::rng(2);
+        let samples = 2_000;
+        let realizations: Vec<f64> = (0..samples)
+            .map(|_| skew_normal.sample_between(lower, upper, &mut rng))
+            .collect();
+        assert!(realizations.iter().all(|x| lower <= *x && *x <= upper));
+
+        // Mean of the truncated standard normal
+        let normal = crate::Normal::new(0., 1.).unwrap();
+        let upper_tail = |x: f64| 0.5 * statrs::function::erf::erfc(x / f64::consts::SQRT_2);
+        let expected =
+            (normal.pdf(lower) - normal.pdf(upper)) / (upper_tail(lower) - upper_tail(upper));
+        let result = realizations.iter().sum::<f64>() / samples as f64;
+        println!("computed value: {:?}, expected: {:?}", result, expected);
+        assert!((result - expected).abs() < 1e-2);
+    }
+
+    #[test_case(0., 1., 0., 0.7; "normal")]
+    #[test_case(1., 0.3, -2., 1.2; "narrow negative shape")]
+    #[test_case(-0.01, 1e-3, 5., -0.0115; "lower tail")]
+    fn ln_pdf(location: f64, scale: f64, shape: f64, x: f64) {
+        let skew_normal = SkewNormal::new(location, scale, shape).unwrap();
+        let expected = skew_normal.pdf(x).ln();
+        assert!((skew_normal.ln_pdf(x) - expected).abs() < 1e-12 * expected.abs().max(1.));
+    }
+
+    #[test]
+    fn ln_normal_cdf_asymptotic() {
+        let normal = crate::Normal::new(0., 1.).unwrap();
+        for &x in &[
+            -35.,
+            -ASYMPTOTIC_NORMAL_TAIL - 1e-9,
+            -ASYMPTOTIC_NORMAL_TAIL + 1e-9,
+        ] {
+            let expected = normal.cdf(x).ln();
+            assert!((ln_normal_cdf(x) - expected).abs() < 1e-10 * expected.abs());
+        }
+        // Where the density underflows, its logarithm is still finite
+        let skew_normal = SkewNormal::new(0., 1., 100.).unwrap();
+        assert_eq!(skew_normal.pdf(-5.), 0.);
+        assert!(skew_normal.ln_pdf(-5.).is_finite());
+    }
+
+    #[test]
+    fn ln_likelihood_derivatives_finite_differences() {
+        let data: Vec<f64> = SkewNormal::new(0.5, 2., -3.)
+            .unwrap()
+            .sample_iter(crate::tests::rng(3))
+            .take(50)
+            .collect();
+        let parameters = [0.3, 1.5, -2.];
+        let (ln_likelihood, gradient, hessian) = ln_likelihood_derivatives(parameters, &data);
+        assert!((ln_likelihood - ln_likelihood_sum(parameters, &data)).abs() < 1e-10);
+
+        let step = 1e-6;
+        for i in 0..3 {
+            let (mut forward, mut backward) = (parameters, parameters);
+            forward[i] += step;
+            backward[i] -= step;
+            let (ln_forward, gradient_forward, _) = ln_likelihood_derivatives(forward, &data);
+            let (ln_backward, gradient_backward, _) = ln_likelihood_derivatives(backward, &data);
+            let difference = (ln_forward - ln_backward) / (2. * step);
+            assert!((gradient[i] - difference).abs() < 1e-5 * gradient[i].abs().max(1.));
+            for j in 0..3 {
+                let difference = (gradient_forward[j] - gradient_backward[j]) / (2. * step);
+                assert!((hessian[j][i] - difference).abs() < 1e-5 * hessian[j][i].abs().max(1.));
+            }
+        }
+    }
+
+    #[test_case(0., 1., 0.; "normal")]
+    #[test_case(2., 3., 4.; "positive shape")]
+    #[test_case(-0.01, 0.02, -3.; "selection coefficients")]
+    fn fit(location: f64, scale: f64, shape: f64) {
+        let data: Vec<f64> = SkewNormal::new(location, scale, shape)
+            .unwrap()
+            .sample_iter(crate::tests::rng(4))
+            .take(5_000)
+            .collect();
+        let fit = SkewNormal::fit(&data).unwrap();
+        let estimate = fit.distribution();
+        let standard_errors = fit.standard_errors();
+        println!(
+            "estimate: {:?}, standard errors: {:?}",
+            estimate, standard_errors
+        );
+
+        let estimates = [estimate.location, estimate.scale, estimate.shape];
+        let parameters = [location, scale, shape];
+        for i in 0..3 {
+            assert!((estimates[i] - parameters[i]).abs() < 4. * standard_errors[i]);
+        }
+        let ln_likelihood: f64 = data.iter().map(|&x| estimate.ln_pdf(x)).sum();
+        assert!((fit.ln_likelihood() - ln_likelihood).abs() < 1e-9 * ln_likelihood.abs());
+        // A maximum within the parameter space
+        for i in 0..3 {
+            for &sign in &[-1., 1.] {
+                let mut perturbed = estimates;
+                perturbed[i] += sign * 1e-3 * standard_errors[i];
+                assert!(ln_likelihood_sum(perturbed, &data) <= fit.ln_likelihood());
+            }
+        }
+    }
+
+    #[test_case(0., 1., 0., 5_000, 200; "normal")]
+    #[test_case(2., 3., 4., 100_000, 50; "positive shape")]
+    #[test_case(-0.01, 0.02, -3., 50_000, 50; "selection coefficients")]
+    fn fit_converges(location: f64, scale: f64, shape: f64, samples: usize, seeds: u64) {
+        let distribution = SkewNormal::new(location, scale, shape).unwrap();
+        let failures: Vec<u64> = (0..seeds)
+            .into_par_iter()
+            .filter(|&seed| {
+                let data: Vec<f64> = distribution
+                    .sample_iter(crate::tests::rng(seed))
+                    .take(samples)
+                    .collect();
+                SkewNormal::fit(&data).is_err()
+            })
+            .collect();
+        assert!(failures.is_empty(), "failed for seeds {:?}", failures);
+    }
+
+    #[test_case(&[1., 2.]; "too few values")]
+    #[test_case(&[1., 1., 1., 1.]; "no spread")]
+    #[test_case(&[1., f64::NAN, 2., 3.]; "not finite")]
+    fn fit_invalid(data: &[f64]) {
+        assert!(matches!(SkewNormal::fit(data), Err(StatsError::BadParams)));
     }
 }
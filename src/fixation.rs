@@ -0,0 +1,369 @@
+//! Fixation of mutations under selection and genetic drift.
+//!
+//! In the diffusion approximation, a mutation with selection `s` and dominance `h`
+//! (genotypes with relative fitness `1`, `1 + h s` and `1 + s`) at frequency `p`
+//! in a population of size `N` becomes fixed with probability (Kimura, 1962)
+//!
+//! `u(p) = \int_0^p G(y) dy / \int_0^1 G(y) dy`, where `G(y) = exp(-2 N s (2 h y + (1 - 2 h) y^2))`.
+//!
+//! The expected times to fixation and to loss, conditioned on each outcome,
+//! follow Kimura and Ohta (1969).
+
+// Structs
+use crate::error::{Result, StatsError};
+
+// Functions
+use quadrature::integrate;
+
+// Constants
+use crate::constants::EPS;
+
+/// Fate of a mutation in a population of constant size.
+///
+/// # Examples
+///
+/// ```
+/// use sandpiper::fixation::Fixation;
+///
+/// let fixation = Fixation::new(1000, 0., 0.5).unwrap();
+/// assert!((fixation.probability() - 1. / 2000.).abs() < 1e-12);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Fixation {
+    population: u64, // N
+    selection: f64,  // s
+    dominance: f64,  // h
+}
+
+impl Fixation {
+    /// Constructs the fate of a mutation with the given selection and dominance
+    /// in a population of size `population`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `population` is zero, if `selection` is not finite,
+    /// or if `dominance` is not in the interval [0, 1].
+    pub fn new(population: u64, selection: f64, dominance: f64) -> Result<Self> {
+        if population == 0 || !selection.is_finite() || !(0.0..=1.0).contains(&dominance) {
+            return Err(StatsError::BadParams);
+        }
+        Ok(Fixation {
+            population,
+            selection,
+            dominance,
+        })
+    }
+
+    /// Returns the fixation probability of a new mutation, at frequency `1 / (2N)`.
+    pub fn probability(&self) -> f64 {
+        self.probability_from(self.new_mutation())
+    }
+
+    /// Returns the fixation probability of a mutation at the given frequency.
+    ///
+    /// # Panics
+    ///
+    /// If `frequency` is not in the interval [0, 1].
+    pub fn probability_from(&self, frequency: f64) -> f64 {
+        self.ln_probability_from(frequency).exp()
+    }
+
+    /// Returns the logarithm of the fixation probability of a new mutation,
+    /// which stays finite when the probability underflows.
+    pub fn ln_probability(&self) -> f64 {
+        self.ln_probability_from(self.new_mutation())
+    }
+
+    /// Returns the logarithm of the fixation probability of a mutation at the given frequency.
+    ///
+    /// The integrals are computed from the logarithm of their largest integrand,
+    /// so that strong selection neither overflows nor underflows,
+    /// and weak selection reduces to the neutral probability `frequency` without cancellation.
+    ///
+    /// # Panics
+    ///
+    /// If `frequency` is not in the interval [0, 1].
+    pub fn ln_probability_from(&self, frequency: f64) -> f64 {
+        assert!(
+            (0.0..=1.0).contains(&frequency),
+            "frequency must be in [0, 1]"
+        );
+        if frequency == 0. || frequency == 1. || self.selection == 0. {
+            return frequency.ln();
+        }
+        self.ln_integral(0., frequency) - self.ln_integral(0., 1.)
+    }
+
+    /// Returns the expected number of generations until a new mutation is fixed,
+    /// conditioned on its fixation.
+    pub fn time_to_fixation(&self) -> f64 {
+        self.time_to_fixation_from(self.new_mutation())
+    }
+
+    /// Returns the expected number of generations until a mutation at the given frequency is fixed,
+    /// conditioned on its fixation.
+    ///
+    /// # Panics
+    ///
+    /// If `frequency` is not in the interval (0, 1].
+    pub fn time_to_fixation_from(&self, frequency: f64) -> f64 {
+        assert!(
+            0. < frequency && frequency <= 1.,
+            "frequency must be in (0, 1]"
+        );
+        if frequency == 1. {
+            return 0.;
+        }
+        let ln_total = self.ln_integral(0., 1.);
+        let ln_probability = self.ln_integral(0., frequency) - ln_total;
+        let ln_complement = self.ln_integral(frequency, 1.) - ln_total;
+
+        let before = self.sojourn(0., frequency, 2, 0);
+        let after = self.sojourn(frequency, 1., 1, 1);
+        4. * self.population as f64 * (after + (ln_complement - ln_probability).exp() * before)
+    }
+
+    /// Returns the expected number of generations until a new mutation is lost,
+    /// conditioned on its loss.
+    pub fn time_to_loss(&self) -> f64 {
+        self.time_to_loss_from(self.new_mutation())
+    }
+
+    /// Returns the expected number of generations until a mutation at the given frequency is lost,
+    /// conditioned on its loss.
+    ///
+    /// # Panics
+    ///
+    /// If `frequency` is not in the interval [0, 1).
+    pub fn time_to_loss_from(&self, frequency: f64) -> f64 {
+        assert!(
+            (0.0..1.0).contains(&frequency),
+            "frequency must be in [0, 1)"
+        );
+        if frequency == 0. {
+            return 0.;
+        }
+        let ln_total = self.ln_integral(0., 1.);
+        let ln_probability = self.ln_integral(0., frequency) - ln_total;
+        let ln_complement = self.ln_integral(frequency, 1.) - ln_total;
+
+        let before = self.sojourn(0., frequency, 1, 1);
+        let after = self.sojourn(frequency, 1., 0, 2);
+        4. * self.population as f64 * (before + (ln_probability - ln_complement).exp() * after)
+    }
+
+    /// Frequency of a new mutation.
+    fn new_mutation(&self) -> f64 {
+        1. / (2. * self.population as f64)
+    }
+
+    /// Returns `2 N s`.
+    fn scaled_selection(&self) -> f64 {
+        2. * self.population as f64 * self.selection
+    }
+
+    /// Returns `q(y) - q(c)`, where `q(y) = 2 h y + (1 - 2 h) y^2`.
+    fn fitness_difference(&self, y: f64, c: f64) -> f64 {
+        let h = self.dominance;
+        (y - c) * (2. * h + (1. - 2. * h) * (y + c))
+    }
+
+    /// Returns the logarithm of `\int_lower^upper G(y) dy`.
+    ///
+    /// Since `q` is increasing over [0, 1], the integrand is largest at `lower` for beneficial mutations
+    /// and at `upper` for deleterious ones. The integral is computed relative to that largest value,
+    /// after changing variables to the distance from it in units of the width of the peak,
+    /// with breakpoints at powers of ten of that width.
+    fn ln_integral(&self, lower: f64, upper: f64) -> f64 {
+        let gamma = self.scaled_selection();
+        let h = self.dominance;
+        let (peak, direction) = if gamma >= 0. {
+            (lower, 1.)
+        } else {
+            (upper, -1.)
+        };
+
+        let slope = (gamma * (2. * h + 2. * (1. - 2. * h) * peak)).abs();
+        let curvature = (2. * gamma * (1. - 2. * h)).abs();
+        let width = slope
+            .recip()
+            .min((2. / curvature).sqrt())
+            .min(upper - lower);
+        let length = (upper - lower) / width;
+
+        let integrand = |t: f64| -> f64 {
+            let y = peak + direction * width * t;
+            (-gamma * self.fitness_difference(y, peak)).exp()
+        };
+        let mut relative = 0.;
+        let mut start = 0.;
+        let mut end = 1f64.min(length);
+        while start < length {
+            relative += integrate(integrand, start, end, EPS).integral;
+            start = end;
+            end = (10. * end).min(length);
+        }
+
+        -gamma * self.fitness_difference(peak, 0.) + width.ln() + relative.ln()
+    }
+
+    /// Integrates `psi(x) u(x)^i (1 - u(x))^j / (4N)` over `[lower, upper]`,
+    /// where `psi(x) = 2 \int_0^1 G / (V(x) G(x))` and `V(x) = x (1 - x) / (2N)`.
+    ///
+    /// Breakpoints are placed at `10^k / (2N)` from both ends of [0, 1], since the integrand varies
+    /// on the scale of a single copy close to loss and to fixation.
+    fn sojourn(&self, lower: f64, upper: f64, i: i32, j: i32) -> f64 {
+        let gamma = self.scaled_selection();
+        let ln_total = self.ln_integral(0., 1.);
+        let integrand = |x: f64| -> f64 {
+            if x <= 0. || x >= 1. {
+                return 0.;
+            }
+            let ln_value = i as f64 * self.ln_integral(0., x) + j as f64 * self.ln_integral(x, 1.)
+                - (i + j - 1) as f64 * ln_total
+                - x.ln()
+                - (-x).ln_1p()
+                + gamma * self.fitness_difference(x, 0.);
+            ln_value.exp()
+        };
+
+        let mut breakpoints = vec![lower, upper, 0.5];
+        let mut distance = self.new_mutation();
+        while distance < 0.5 {
+            breakpoints.push(distance);
+            breakpoints.push(1. - distance);
+            distance *= 10.;
+        }
+        breakpoints.retain(|x| lower <= *x && *x <= upper);
+        breakpoints.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        breakpoints.dedup();
+
+        breakpoints
+            .windows(2)
+            .map(|window| integrate(integrand, window[0], window[1], EPS).integral)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(1000, 1e-3; "weak beneficial")]
+    #[test_case(1000, -1e-3; "weak deleterious")]
+    #[test_case(20_000, 0.1; "strong beneficial")]
+    #[test_case(20_000, -1e-3; "strong deleterious")]
+    #[test_case(1000, 1e-9; "nearly neutral")]
+    fn probability_additive(population: u64, selection: f64) {
+        let fixation = Fixation::new(population, selection, 0.5).unwrap();
+        // Kimura's formula for additive mutations
+        let gamma = 2. * population as f64 * selection;
+        let p = 1. / (2. * population as f64);
+        let expected = (-gamma * p).exp_m1() / (-gamma).exp_m1();
+        let result = fixation.probability();
+        assert!((result - expected).abs() < 1e-8 * expected);
+    }
+
+    #[test_case(1000, 1e-3, 0.2; "beneficial")]
+    #[test_case(500_000, -1e-4, 0.; "recessive deleterious")]
+    #[test_case(500_000, 1e-2, 1.; "dominant beneficial")]
+    fn probability_symmetry(population: u64, selection: f64, dominance: f64) {
+        let frequency = 0.3;
+        let fixation = Fixation::new(population, selection, dominance).unwrap();
+        let reflected = Fixation::new(population, -selection, 1. - dominance).unwrap();
+
+        let result =
+            fixation.probability_from(frequency) + reflected.probability_from(1. - frequency);
+        assert!((result - 1.).abs() < 1e-10);
+    }
+
+    #[test_case(1000; "small population")]
+    #[test_case(20_000; "sandpiper")]
+    fn times_neutral(population: u64) {
+        let fixation = Fixation::new(population, 0., 0.5).unwrap();
+        let n = population as f64;
+        let p = 1. / (2. * n);
+
+        let expected = -4. * n * (1. - p) * (-p).ln_1p() / p;
+        assert!((fixation.time_to_fixation() - expected).abs() < 1e-6 * expected);
+        let expected = -4. * n * p * p.ln() / (1. - p);
+        assert!((fixation.time_to_loss() - expected).abs() < 1e-6 * expected);
+    }
+
+    #[test]
+    fn time_to_fixation_strong_selection() {
+        // Kimura and Ohta (1969), with heterozygote advantage s / 2
+        let (population, selection) = (100_000, 0.01);
+        let fixation = Fixation::new(population, selection, 0.5).unwrap();
+        let gamma = 2. * population as f64 * selection;
+        let expected = 4. * (gamma.ln() + 0.5772156649015329 - 1. / gamma) / selection;
+
+        let result = fixation.time_to_fixation();
+        assert!((result - expected).abs() < 1e-3 * expected);
+    }
+
+    #[test_case(0, 0., 0.5; "empty population")]
+    #[test_case(1000, f64::NAN, 0.5; "nan selection")]
+    #[test_case(1000, 0., 1.5; "dominance above one")]
+    fn new_invalid(population: u64, selection: f64, dominance: f64) {
+        assert!(Fixation::new(population, selection, dominance).is_err());
+    }
+
+    /// Laplace approximation of the logarithm of the fixation probability of a new mutation,
+    /// with relative error of order `1 / |2Ns|`.
+    fn ln_probability_strong_selection(population: u64, selection: f64, dominance: f64) -> f64 {
+        let gamma = 2. * population as f64 * selection;
+        let ln_new = if dominance == 0. {
+            -(2. * population as f64).ln()
+        } else {
+            ((-2. * dominance * selection).exp_m1() / (-2. * dominance * gamma)).ln()
+        };
+        // The integrand of the total is largest at 0 for beneficial mutations and at 1 otherwise,
+        // where it has zero slope only for h = 0 and h = 1 respectively
+        let ln_total = if gamma > 0. && dominance == 0. {
+            (std::f64::consts::PI / gamma).sqrt().ln() - 2f64.ln()
+        } else if gamma > 0. {
+            -(2. * dominance * gamma).ln()
+        } else if dominance == 1. {
+            -gamma + (std::f64::consts::PI / -gamma).sqrt().ln() - 2f64.ln()
+        } else {
+            -gamma - (-2. * gamma * (1. - dominance)).ln()
+        };
+        ln_new - ln_total
+    }
+
+    #[test_case(crate::N_SANDPIPER, -1.; "sandpiper lethal")]
+    #[test_case(crate::N_SANDPIPER, 1.; "sandpiper beneficial")]
+    #[test_case(crate::N_REDNECK, -1.; "redneck lethal")]
+    #[test_case(crate::N_REDNECK, -0.1; "redneck deleterious")]
+    #[test_case(crate::N_REDNECK, 1.; "redneck beneficial")]
+    fn ln_probability_extreme_corners(population: u64, selection: f64) {
+        for &dominance in &[0., 0.25, 0.5, 1.] {
+            let fixation = Fixation::new(population, selection, dominance).unwrap();
+            let expected = ln_probability_strong_selection(population, selection, dominance);
+
+            let result = fixation.ln_probability();
+            assert!(result.is_finite());
+            assert!((result - expected).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn ln_probability_sweep_grid() {
+        // Selection from -10 to 10, as integrated by `Substitutions::mean`
+        let selections = (-9..=1)
+            .map(|k| 10f64.powi(k))
+            .flat_map(|s| vec![-s, s])
+            .chain(vec![-10., 0., 10.]);
+        for population in [1, 100, crate::N_SANDPIPER, crate::N_REDNECK] {
+            for s in selections.clone() {
+                for &h in &[0., 0.1, 0.5, 0.9, 1.] {
+                    let fixation = Fixation::new(population, s, h).unwrap();
+                    let result = fixation.ln_probability();
+                    assert!(result.is_finite() && result <= 1e-12);
+                }
+            }
+        }
+    }
+}
@@ -5,7 +5,10 @@
 pub use self::constants::*;
 pub use self::distribution::*;
 pub use self::parameters::Parameters;
-pub use self::root_finding::ExpBinary;
+pub use self::root_finding::{
+    Bisection, Brent, ExpBinary, Illinois, ProbabilisticBisection, Refinement, RootError,
+    RootFinder, RootReport, StochasticRoot,
+};
 pub use self::statistic::*;
 
 /// Empirical data and overall constants.
@@ -14,6 +17,10 @@ mod constants;
 pub mod distribution;
 /// Errors and results from this crate.
 pub mod error;
+/// Fixation probabilities and times.
+pub mod fixation;
+/// McDonald–Kreitman statistics.
+pub mod mcdonald_kreitman;
 /// Parameters of the model.
 mod parameters;
 /// Root finding algorithms.
@@ -24,7 +31,7 @@ mod statistic;
 pub mod prelude {
     pub use crate::constants::*;
     pub use crate::distribution::*;
-    pub use crate::{Parameters, Substitutions};
+    pub use crate::{Parameters, SiteModel, SubstitutionCounts, Substitutions};
     pub use statrs::statistics::Mean;
 }
 
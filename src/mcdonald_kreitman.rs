@@ -0,0 +1,229 @@
+//! McDonald–Kreitman statistics predicted from the distribution of fitness effects.
+//!
+//! Selected (nonsynonymous) sites follow the given selection and dominance,
+//! while neutral (synonymous) sites have no selection. Polymorphism is measured by the expected heterozygosity
+//! per site and divergence by the expected fraction of sites with a substitution.
+
+// Structs
+use crate::error::{Result, StatsError};
+use crate::{Dominance, Heterozygosity, Selection, Substitutions};
+
+/// Predicted polymorphism and divergence of selected and neutral sites.
+///
+/// # Examples
+///
+/// ```
+/// use sandpiper::mcdonald_kreitman::McDonaldKreitman;
+/// use sandpiper::{Dominance, Selection};
+///
+/// let selection = Selection::Mixture(vec![
+///     (0.9, Selection::Gamma { shape: 0.3, scale: 1e-3 }),
+///     (0.1, Selection::Fixed(1e-3)),
+/// ]);
+/// let mk = McDonaldKreitman::new(20_000, 1.2e-8, 200_000., selection, Dominance::Fixed(0.5)).unwrap();
+/// assert!(mk.alpha() > 0.);
+/// // Beneficial mutations increase divergence, while all selected mutations reduce polymorphism
+/// assert!(mk.dn_ds() > 1.);
+/// assert!(mk.pn_ps() < 1.);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct McDonaldKreitman {
+    pn: f64,
+    ps: f64,
+    dn: f64,
+    ds: f64,
+    adaptive_dn: f64,
+}
+
+impl McDonaldKreitman {
+    /// Computes the statistics for a population of size `population`,
+    /// with the given mutation rate per site and generation and divergence time in generations.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `population` is zero, if `mutation_rate` is not positive,
+    /// if `divergence_time` is not finite and non-negative,
+    /// or if `Heterozygosity` or `Substitutions` reject the selection or dominance.
+    pub fn new(
+        population: u64,
+        mutation_rate: f64,
+        divergence_time: f64,
+        selection: Selection,
+        dominance: Dominance,
+    ) -> Result<Self> {
+        if population == 0
+            || !(mutation_rate > 0. && mutation_rate.is_finite())
+            || !(divergence_time >= 0. && divergence_time.is_finite())
+        {
+            return Err(StatsError::BadParams);
+        }
+        let neutral = Selection::Fixed(0.);
+        let additive = Dominance::Fixed(0.5);
+
+        let pn = Heterozygosity::new(
+            population,
+            mutation_rate,
+            selection.clone(),
+            dominance.clone(),
+        )?
+        .expected_heterozygosity()
+        .integral;
+        let ps = Heterozygosity::new(population, mutation_rate, neutral.clone(), additive.clone())?
+            .expected_heterozygosity()
+            .integral;
+
+        let substitutions = Substitutions::with_divergence(
+            population,
+            mutation_rate,
+            divergence_time,
+            selection,
+            dominance,
+        )?;
+        let dn = substitutions.expected_substitutions().integral;
+        let adaptive_dn = substitutions.expected_adaptive_substitutions().integral;
+        let ds = Substitutions::with_divergence(
+            population,
+            mutation_rate,
+            divergence_time,
+            neutral,
+            additive,
+        )?
+        .expected_substitutions()
+        .integral;
+
+        Ok(McDonaldKreitman {
+            pn,
+            ps,
+            dn,
+            ds,
+            adaptive_dn,
+        })
+    }
+
+    /// Expected heterozygosity per selected site.
+    pub fn pn(&self) -> f64 {
+        self.pn
+    }
+
+    /// Expected heterozygosity per neutral site.
+    pub fn ps(&self) -> f64 {
+        self.ps
+    }
+
+    /// Expected substitutions per selected site.
+    pub fn dn(&self) -> f64 {
+        self.dn
+    }
+
+    /// Expected substitutions per neutral site.
+    pub fn ds(&self) -> f64 {
+        self.ds
+    }
+
+    /// Returns the expected table for the given numbers of sites,
+    /// with polymorphisms in the first row, substitutions in the second one,
+    /// and selected and neutral sites in the first and second columns.
+    pub fn table(&self, selected_sites: f64, neutral_sites: f64) -> [[f64; 2]; 2] {
+        [
+            [self.pn * selected_sites, self.ps * neutral_sites],
+            [self.dn * selected_sites, self.ds * neutral_sites],
+        ]
+    }
+
+    /// Ratio of polymorphism at selected and neutral sites.
+    pub fn pn_ps(&self) -> f64 {
+        self.pn / self.ps
+    }
+
+    /// Ratio of divergence at selected and neutral sites.
+    pub fn dn_ds(&self) -> f64 {
+        self.dn / self.ds
+    }
+
+    /// Proportion of substitutions at selected sites coming from beneficial mutations.
+    pub fn alpha(&self) -> f64 {
+        self.adaptive_dn / self.dn
+    }
+
+    /// Estimate `1 - (dS pN) / (dN pS)` of the proportion of adaptive substitutions
+    /// from the McDonald–Kreitman table, as computed from data.
+    ///
+    /// It differs from `alpha` when slightly deleterious mutations contribute to polymorphism.
+    pub fn alpha_estimate(&self) -> f64 {
+        1. - self.pn_ps() / self.dn_ds()
+    }
+
+    /// Rate of adaptive substitution relative to the neutral divergence, `alpha * dN / dS`.
+    pub fn omega_a(&self) -> f64 {
+        self.adaptive_dn / self.ds
+    }
+
+    /// Rate of non-adaptive substitution relative to the neutral divergence, `(1 - alpha) * dN / dS`.
+    pub fn omega_na(&self) -> f64 {
+        (self.dn - self.adaptive_dn) / self.ds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn neutral() {
+        let mk =
+            McDonaldKreitman::new(1000, 1e-6, 1e5, Selection::Fixed(0.), Dominance::Fixed(0.5))
+                .unwrap();
+
+        assert!((mk.pn_ps() - 1.).abs() < 1e-12);
+        assert!((mk.dn_ds() - 1.).abs() < 1e-12);
+        assert_eq!(mk.alpha(), 0.);
+        assert_eq!(mk.omega_a(), 0.);
+        assert!(mk.alpha_estimate().abs() < 1e-12);
+    }
+
+    #[test_case(0, 1e-6, 1e5; "empty population")]
+    #[test_case(1000, 0., 1e5; "zero mutation rate")]
+    #[test_case(1000, 1e-6, -1.; "negative divergence time")]
+    #[test_case(1000, 1e-6, f64::INFINITY; "infinite divergence time")]
+    fn new_invalid(population: u64, mutation_rate: f64, divergence_time: f64) {
+        let result = McDonaldKreitman::new(
+            population,
+            mutation_rate,
+            divergence_time,
+            Selection::Fixed(0.),
+            Dominance::Fixed(0.5),
+        );
+        assert!(matches!(result, Err(StatsError::BadParams)));
+    }
+
+    #[test_case(Selection::Gamma { shape: 0.3, scale: 1e-3 }; "gamma")]
+    #[test_case(Selection::Fixed(-1e-4); "slightly deleterious")]
+    fn deleterious(selection: Selection) {
+        let mk =
+            McDonaldKreitman::new(10_000, 1e-8, 1e6, selection, Dominance::Fixed(0.5)).unwrap();
+
+        assert_eq!(mk.alpha(), 0.);
+        assert!(mk.dn_ds() < 1.);
+        assert!(mk.pn_ps() < 1.);
+        // Slightly deleterious polymorphisms bias the estimate downwards
+        assert!(mk.alpha_estimate() < 0.);
+        assert!((mk.omega_na() - mk.dn_ds()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn adaptive() {
+        let selection = Selection::Mixture(vec![
+            (0.5, Selection::Fixed(-1e-2)),
+            (0.5, Selection::Fixed(1e-3)),
+        ]);
+        let mk =
+            McDonaldKreitman::new(10_000, 1e-8, 1e6, selection, Dominance::Fixed(0.5)).unwrap();
+
+        assert!(mk.alpha() > 0.99);
+        assert!((mk.omega_a() - mk.alpha() * mk.dn_ds()).abs() < 1e-12 * mk.omega_a());
+        assert!((mk.omega_a() + mk.omega_na() - mk.dn_ds()).abs() < 1e-12 * mk.dn_ds());
+        let table = mk.table(1000., 500.);
+        assert!((table[1][0] / table[1][1] - 2. * mk.dn_ds()).abs() < 1e-12);
+    }
+}
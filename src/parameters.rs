@@ -1,5 +1,6 @@
 use crate::distribution::SkewNormal;
 use crate::error::Result;
+use crate::{Dominance, Selection};
 
 /// Parameters of the model.
 ///
@@ -23,4 +24,19 @@ impl Parameters {
             skew_normal,
         })
     }
+
+    /// Skew-normal selection with location `mu`, scale `sigma` and shape `alpha`.
+    pub fn selection(&self) -> Selection {
+        Selection::SkewNormal {
+            location: self.mu,
+            scale: self.sigma,
+            shape: self.alpha,
+            bounds: None,
+        }
+    }
+
+    /// Exponential dominance with rate `beta`.
+    pub fn dominance(&self) -> Dominance {
+        Dominance::Exponential { rate: self.beta }
+    }
 }
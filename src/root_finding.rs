@@ -1,5 +1,57 @@
-use anyhow::{anyhow, Result};
+use average::Estimate;
 use getset::{Getters, Setters};
+use std::{error, fmt};
+
+/// Result of root finding, with `RootError` as error type.
+pub type Result<T> = std::result::Result<T, RootError>;
+
+/// Error type returned by root finding.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RootError {
+    /// The function evaluates to `NaN` or an infinite value at `x`.
+    NotFinite { x: f64, value: f64 },
+    /// The exponential search found no change of sign within `max_exp_iterations` iterations.
+    NoBracket { iterations: u64 },
+    /// The settings of the root finder are invalid.
+    BadParams,
+}
+
+impl fmt::Display for RootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RootError::NotFinite { x, value } => {
+                write!(f, "Function evaluates to {} at {}", value, x)
+            }
+            RootError::NoBracket { iterations } => write!(
+                f,
+                "Early stopped exponential search: {} iterations reached",
+                iterations
+            ),
+            RootError::BadParams => write!(f, "Bad root finding parameters"),
+        }
+    }
+}
+
+impl error::Error for RootError {}
+
+/// Summary of a root search by `ExpBinary::run_report`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RootReport {
+    /// Root found.
+    pub root: f64,
+    /// Value of the function at the root.
+    pub value: f64,
+    /// Lower bound of the final interval.
+    pub lower: f64,
+    /// Upper bound of the final interval.
+    pub upper: f64,
+    /// Number of evaluations of the function, each at a different point.
+    pub evaluations: usize,
+    /// Number of steps of the exponential search.
+    pub exp_iterations: u64,
+    /// Number of iterations of the refinement method.
+    pub iterations: usize,
+}
 
 /// Root finding method with exponential search for space together with binary search for root.
 ///
@@ -7,13 +59,29 @@ use getset::{Getters, Setters};
 /// there is no known bound on the space in which the root might be. From an intial point,
 /// the algorithm expands the lower and upper bounds in both directions at exponential rate.
 /// Once an interval where the lower bound evaluates a different sign from the upper bound
-/// is found, the binary search starts.
+/// is found, the interval is refined by `method`, bisection by default.
+///
+/// # Examples
+///
+/// Brent's method needs far fewer evaluations than bisection for small tolerances.
+/// ```
+/// use sandpiper::{Brent, ExpBinary};
+///
+/// let mut root_finding = ExpBinary::with_method(|x: f64| x.exp() - 2., 0., Brent);
+/// root_finding.set_tol(1e-15);
+/// let root = root_finding.run().unwrap();
+/// assert!((root - 2f64.ln()).abs() < 1e-14);
+/// ```
 #[derive(Debug, Getters, Setters)]
-pub struct ExpBinary<F> {
+pub struct ExpBinary<F, M = Bisection> {
     /// Function to find the root of.
     #[getset(set = "pub", get = "pub")]
     f: F,
 
+    /// Method refining the interval found by the exponential search.
+    #[getset(set = "pub", get = "pub")]
+    method: M,
+
     /// Tolerance when searching the root.
     ///
     /// This tolerance refers to the size of the interval in which the root is to be founded.
@@ -43,12 +111,23 @@ pub struct ExpBinary<F> {
     lower: f64,
 }
 
-impl<F> ExpBinary<F>
+impl<F> ExpBinary<F, Bisection>
 where
     F: Fn(f64) -> f64,
 {
-    /// Constructor
+    /// Constructor, refining the interval by bisection.
     pub fn new(f: F, init: f64) -> Self {
+        ExpBinary::with_method(f, init, Bisection)
+    }
+}
+
+impl<F, M> ExpBinary<F, M>
+where
+    F: Fn(f64) -> f64,
+    M: RootFinder,
+{
+    /// Constructor, refining the interval with the given method.
+    pub fn with_method(f: F, init: f64, method: M) -> Self {
         // Dafualt values
         let tol = 1e-10;
         let maximum = std::f64::INFINITY;
@@ -61,6 +140,7 @@ where
         let lower = init;
         ExpBinary {
             f,
+            method,
             tol,
             minimum,
             maximum,
@@ -72,69 +152,667 @@ where
         }
     }
 
+    /// Returns a root of the function.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RootError::NotFinite` if the function evaluates to `NaN` or an infinite value,
+    /// and `RootError::NoBracket` if the exponential search does not find a change of sign.
     pub fn run(&mut self) -> Result<f64> {
-        // Exponential search
+        let mut evaluations = 0;
+        self.search(&mut evaluations)
+            .map(|refinement| refinement.root)
+    }
+
+    /// Returns a root of the function together with diagnostics of the search.
+    ///
+    /// The function is evaluated at most once at each point,
+    /// which includes the root if the refinement method did not evaluate it.
+    ///
+    /// # Errors
+    ///
+    /// See `run`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::{Brent, ExpBinary};
+    ///
+    /// let mut root_finding = ExpBinary::with_method(|x: f64| x * x - 2., 1., Brent);
+    /// let report = root_finding.run_report().unwrap();
+    /// assert!((report.root - 2f64.sqrt()).abs() < 1e-10);
+    /// assert!(report.lower <= report.root && report.root <= report.upper);
+    /// assert!(report.value.abs() < 1e-9);
+    /// println!("{} evaluations", report.evaluations);
+    /// ```
+    pub fn run_report(&mut self) -> Result<RootReport> {
+        let mut evaluations = 0;
+        let refinement = self.search(&mut evaluations)?;
+        let value = if refinement.root == refinement.lower.0 {
+            refinement.lower.1
+        } else if refinement.root == refinement.upper.0 {
+            refinement.upper.1
+        } else {
+            evaluate(&self.f, refinement.root, &mut evaluations)?
+        };
+        Ok(RootReport {
+            root: refinement.root,
+            value,
+            lower: refinement.lower.0,
+            upper: refinement.upper.0,
+            evaluations,
+            exp_iterations: self.counter_exp_iteration,
+            iterations: refinement.iterations,
+        })
+    }
+
+    /// Exponential search followed by the refinement method.
+    fn search(&mut self, evaluations: &mut usize) -> Result<Refinement> {
+        let (lower, upper) = self.bracket(evaluations)?;
+        if lower.1 == 0. || upper.1 == 0. {
+            let root = if lower.1 == 0. { lower } else { upper };
+            return Ok(Refinement {
+                root: root.0,
+                lower: root,
+                upper: root,
+                iterations: 0,
+            });
+        }
+        let f = &self.f;
+        self.method
+            .refine(|x| evaluate(f, x, evaluations), lower, upper, self.tol)
+    }
+
+    /// Exponential search of an interval whose bounds evaluate to different signs,
+    /// returned as pairs of a bound and its value.
+    ///
+    /// A bound with a vanishing value ends the search.
+    fn bracket(&mut self, evaluations: &mut usize) -> Result<((f64, f64), (f64, f64))> {
         self.upper = (self.upper + self.step).min(self.maximum);
         self.lower = (self.lower - self.step).max(self.minimum);
         self.step *= 2.;
+        let mut lower_value = evaluate(&self.f, self.lower, evaluations)?;
+        let mut upper_value = if self.upper == self.lower {
+            lower_value
+        } else {
+            evaluate(&self.f, self.upper, evaluations)?
+        };
 
         loop {
             // First step verification
-            if (self.f)(self.lower) * (self.f)(self.upper) < 0. {
+            if !same_sign(lower_value, upper_value) {
                 break;
             }
             // Upper search
             let next_upper = (self.upper + self.step).min(self.maximum);
-            if (self.f)(self.upper) * (self.f)(next_upper) < 0. {
-                self.lower = self.upper;
+            if next_upper != self.upper {
+                let next_upper_value = evaluate(&self.f, next_upper, evaluations)?;
+                if !same_sign(upper_value, next_upper_value) {
+                    self.lower = self.upper;
+                    lower_value = upper_value;
+                    self.upper = next_upper;
+                    upper_value = next_upper_value;
+                    break;
+                }
                 self.upper = next_upper;
-                break;
+                upper_value = next_upper_value;
             }
-            self.upper = next_upper;
 
             // Lower search
             let next_lower = (self.lower - self.step).max(self.minimum);
-            if (self.f)(self.lower) * (self.f)(next_lower) < 0. {
-                self.upper = self.lower;
+            if next_lower != self.lower {
+                let next_lower_value = evaluate(&self.f, next_lower, evaluations)?;
+                if !same_sign(lower_value, next_lower_value) {
+                    self.upper = self.lower;
+                    upper_value = lower_value;
+                    self.lower = next_lower;
+                    lower_value = next_lower_value;
+                    break;
+                }
                 self.lower = next_lower;
-                break;
+                lower_value = next_lower_value;
             }
-            self.lower = next_lower;
 
             // Update
             self.step *= 2.;
             self.counter_exp_iteration += 1;
 
             if self.counter_exp_iteration > self.max_exp_iterations {
-                return Err(anyhow!(
-                    "Early stopped exponential search: {} iterations reached",
-                    self.counter_exp_iteration
-                ));
+                return Err(RootError::NoBracket {
+                    iterations: self.counter_exp_iteration,
+                });
+            }
+        }
+        Ok(((self.lower, lower_value), (self.upper, upper_value)))
+    }
+}
+
+/// Evaluates `f` at `x`, counting the evaluation and checking that the value is finite.
+fn evaluate<F>(f: &F, x: f64, evaluations: &mut usize) -> Result<f64>
+where
+    F: Fn(f64) -> f64,
+{
+    *evaluations += 1;
+    let value = f(x);
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(RootError::NotFinite { x, value })
+    }
+}
+
+/// Outcome of `RootFinder::refine`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Refinement {
+    /// Root found.
+    pub root: f64,
+    /// Lower bound of the final interval, with its value.
+    pub lower: (f64, f64),
+    /// Upper bound of the final interval, with its value.
+    pub upper: (f64, f64),
+    /// Number of iterations, each with one evaluation.
+    pub iterations: usize,
+}
+
+/// Method refining an interval whose bounds evaluate to different signs into a root.
+pub trait RootFinder {
+    /// Returns a point in `[lower, upper]` within `tol` of a root of `f`,
+    /// given the bounds together with their values, which have different signs.
+    ///
+    /// `f` is only evaluated at new points, and its errors are propagated.
+    fn refine<F>(&self, f: F, lower: (f64, f64), upper: (f64, f64), tol: f64) -> Result<Refinement>
+    where
+        F: FnMut(f64) -> Result<f64>;
+}
+
+/// Whether both values are non-zero and have the same sign, so that no root lies between them.
+fn same_sign(a: f64, b: f64) -> bool {
+    (a > 0. && b > 0.) || (a < 0. && b < 0.)
+}
+
+/// Bisection, halving the interval at each evaluation.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Bisection;
+
+impl RootFinder for Bisection {
+    fn refine<F>(
+        &self,
+        mut f: F,
+        mut lower: (f64, f64),
+        mut upper: (f64, f64),
+        tol: f64,
+    ) -> Result<Refinement>
+    where
+        F: FnMut(f64) -> Result<f64>,
+    {
+        let mut iterations = 0;
+        while (lower.0 - upper.0).abs() > tol {
+            let middle = (lower.0 + upper.0) * 0.5;
+            if middle == lower.0 || middle == upper.0 {
+                break;
             }
+            let middle_value = f(middle)?;
+            iterations += 1;
+
+            if middle_value == 0. {
+                lower = (middle, middle_value);
+                upper = lower;
+            } else if same_sign(middle_value, upper.1) {
+                upper = (middle, middle_value);
+            } else {
+                lower = (middle, middle_value);
+            }
+        }
+        Ok(Refinement {
+            root: (lower.0 + upper.0) * 0.5,
+            lower,
+            upper,
+            iterations,
+        })
+    }
+}
+
+/// Brent's method, combining inverse quadratic interpolation and secant steps with bisection.
+///
+/// It converges superlinearly for smooth functions,
+/// while never needing more than about twice the evaluations of bisection.
+///
+/// Reference: R. P. Brent (1973). Algorithms for Minimization without Derivatives, chapter 4.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Brent;
+
+impl RootFinder for Brent {
+    fn refine<F>(
+        &self,
+        mut f: F,
+        lower: (f64, f64),
+        upper: (f64, f64),
+        tol: f64,
+    ) -> Result<Refinement>
+    where
+        F: FnMut(f64) -> Result<f64>,
+    {
+        // `b` is the best estimate, `c` the other end of the interval and `a` the previous value of `b`
+        let (mut a, mut fa) = lower;
+        let (mut b, mut fb) = upper;
+        let (mut c, mut fc) = (a, fa);
+        let mut d = b - a;
+        let mut e = d;
+        let mut iterations = 0;
+        loop {
+            if same_sign(fb, fc) {
+                c = a;
+                fc = fa;
+                d = b - a;
+                e = d;
+            }
+            if fc.abs() < fb.abs() {
+                a = b;
+                b = c;
+                c = a;
+                fa = fb;
+                fb = fc;
+                fc = fa;
+            }
+            let tolerance = 2. * f64::EPSILON * b.abs() + 0.5 * tol;
+            let middle = 0.5 * (c - b);
+            if middle.abs() <= tolerance || fb == 0. {
+                let (lower, upper) = if fb == 0. {
+                    ((b, fb), (b, fb))
+                } else if b <= c {
+                    ((b, fb), (c, fc))
+                } else {
+                    ((c, fc), (b, fb))
+                };
+                return Ok(Refinement {
+                    root: b,
+                    lower,
+                    upper,
+                    iterations,
+                });
+            }
+            if e.abs() >= tolerance && fa.abs() > fb.abs() {
+                // Interpolation
+                let s = fb / fa;
+                let (mut p, mut q) = if a == c {
+                    // Secant
+                    (2. * middle * s, 1. - s)
+                } else {
+                    // Inverse quadratic interpolation
+                    let q = fa / fc;
+                    let r = fb / fc;
+                    (
+                        s * (2. * middle * q * (q - r) - (b - a) * (r - 1.)),
+                        (q - 1.) * (r - 1.) * (s - 1.),
+                    )
+                };
+                if p > 0. {
+                    q = -q;
+                } else {
+                    p = -p;
+                }
+                if 2. * p < (3. * middle * q - (tolerance * q).abs()).min((e * q).abs()) {
+                    e = d;
+                    d = p / q;
+                } else {
+                    d = middle;
+                    e = d;
+                }
+            } else {
+                d = middle;
+                e = d;
+            }
+            a = b;
+            fa = fb;
+            b += if d.abs() > tolerance {
+                d
+            } else {
+                tolerance.copysign(middle)
+            };
+            fb = f(b)?;
+            iterations += 1;
+        }
+    }
+}
+
+/// Regula falsi with the Illinois modification, which halves the value kept at a bound
+/// that is retained twice in a row, so that both bounds converge to the root.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Illinois;
+
+impl RootFinder for Illinois {
+    fn refine<F>(
+        &self,
+        mut f: F,
+        mut lower: (f64, f64),
+        mut upper: (f64, f64),
+        tol: f64,
+    ) -> Result<Refinement>
+    where
+        F: FnMut(f64) -> Result<f64>,
+    {
+        // Values used for interpolation, halved by the Illinois modification
+        let (mut lower_weight, mut upper_weight) = (lower.1, upper.1);
+        // Bound replaced in the previous step: -1 for lower, 1 for upper
+        let mut side = 0;
+        let mut iterations = 0;
+        while (upper.0 - lower.0).abs() > tol {
+            let mut next =
+                (lower.0 * upper_weight - upper.0 * lower_weight) / (upper_weight - lower_weight);
+            if !(next > lower.0.min(upper.0) && next < lower.0.max(upper.0)) {
+                next = (lower.0 + upper.0) * 0.5;
+                if next == lower.0 || next == upper.0 {
+                    break;
+                }
+            }
+            let next_value = f(next)?;
+            iterations += 1;
+            if next_value == 0. {
+                return Ok(Refinement {
+                    root: next,
+                    lower: (next, next_value),
+                    upper: (next, next_value),
+                    iterations,
+                });
+            }
+            if same_sign(next_value, upper.1) {
+                upper = (next, next_value);
+                upper_weight = next_value;
+                if side == 1 {
+                    lower_weight *= 0.5;
+                }
+                side = 1;
+            } else {
+                lower = (next, next_value);
+                lower_weight = next_value;
+                if side == -1 {
+                    upper_weight *= 0.5;
+                }
+                side = -1;
+            }
+        }
+        Ok(Refinement {
+            root: (lower.0 + upper.0) * 0.5,
+            lower,
+            upper,
+            iterations,
+        })
+    }
+}
+
+/// Probabilistic bisection for the root of a function observed with noise,
+/// like a Monte Carlo estimate.
+///
+/// The closure `f` returns one noisy realization whose expectation is the function.
+/// Between `lower` and `upper`, where the function is assumed to change sign once,
+/// the algorithm keeps a distribution of the location of the root, starting uniform.
+/// At each iteration, the function is estimated by the mean of `samples` realizations
+/// at the median of this distribution, and the mass on the side indicated by the sign of the mean
+/// is multiplied by the probability that this sign is correct, estimated from the standard error.
+///
+/// The estimates at both bounds must have significant opposite signs,
+/// which gives the direction of the function.
+/// The confidence interval is the bracket formed by the points whose sign is significant
+/// at level `(1 - confidence) / (max_iterations + 2)`, so that, by the union bound
+/// over these points and the bounds, it contains the root with probability at least `confidence`.
+/// Every third iteration, the function is estimated halfway between the median and one of the bounds
+/// of this bracket instead, so that it shrinks even when the estimates at the median are not significant.
+///
+/// Reference: R. Waeber, P. I. Frazier and S. G. Henderson (2013).
+/// Bisection search with noisy responses. SIAM Journal on Control and Optimization 51, 2261-2279.
+///
+/// # Examples
+///
+/// ```
+/// use rand::Rng;
+/// use sandpiper::ProbabilisticBisection;
+///
+/// let mut rng = rand::thread_rng();
+/// let noisy = |x: f64| x - 0.3 + rng.gen_range(-1., 1.);
+/// let mut root_finding = ProbabilisticBisection::new(noisy, -10., 10., 100);
+/// let root = root_finding.run().unwrap();
+/// println!("Root in [{}, {}] with 95% confidence", root.lower, root.upper);
+/// ```
+#[derive(Debug, Getters, Setters)]
+pub struct ProbabilisticBisection<F> {
+    /// Closure returning a noisy realization of the function.
+    #[getset(set = "pub", get = "pub")]
+    f: F,
+
+    /// Lower bound of the search space.
+    #[getset(set = "pub", get = "pub")]
+    lower: f64,
+
+    /// Upper bound of the search space.
+    #[getset(set = "pub", get = "pub")]
+    upper: f64,
+
+    /// Number of realizations averaged at each point.
+    #[getset(set = "pub", get = "pub")]
+    samples: usize,
+
+    /// Maximum number of points where the function is estimated, besides the bounds.
+    #[getset(set = "pub", get = "pub")]
+    max_iterations: usize,
+
+    /// Width of the confidence interval at which the search stops.
+    #[getset(set = "pub", get = "pub")]
+    tol: f64,
+
+    /// Confidence level of the interval for the root.
+    #[getset(set = "pub", get = "pub")]
+    confidence: f64,
+}
+
+/// Outcome of `ProbabilisticBisection::run`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StochasticRoot {
+    /// Median of the distribution of the root, restricted to the confidence interval.
+    pub root: f64,
+    /// Lower bound of the confidence interval.
+    pub lower: f64,
+    /// Upper bound of the confidence interval.
+    pub upper: f64,
+    /// Number of realizations of the function.
+    pub evaluations: usize,
+    /// Number of points where the function was estimated, besides the bounds.
+    pub iterations: usize,
+}
+
+impl<F> ProbabilisticBisection<F>
+where
+    F: FnMut(f64) -> f64,
+{
+    /// Constructor
+    pub fn new(f: F, lower: f64, upper: f64, samples: usize) -> Self {
+        // Default values
+        let max_iterations = 100;
+        let tol = 0.;
+        let confidence = 0.95;
+
+        ProbabilisticBisection {
+            f,
+            lower,
+            upper,
+            samples,
+            max_iterations,
+            tol,
+            confidence,
         }
-        assert!((self.f)(self.upper) * (self.f)(self.lower) <= 0.0);
+    }
 
-        // Binary search
-        let mut upper_value = (self.f)(self.upper);
+    /// Returns an estimate of the root and an interval that contains it with the given confidence.
+    ///
+    /// # Remarks
+    ///
+    /// The confidence relies on a normal approximation of the mean of the realizations at each point,
+    /// and is conservative because of the union bound. The interval can not be narrower than the region
+    /// where the function is within a few standard errors of zero.
+    /// If all realizations at a bound are zero, it is returned as the root.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RootError::BadParams` if the bounds are not finite and ordered, if `samples < 2`,
+    /// if `max_iterations == 0` or if `confidence` is not in `(0, 1)`,
+    /// `RootError::NotFinite` if a realization is not finite,
+    /// and `RootError::NoBracket` if the estimates at both bounds do not have significant opposite signs.
+    pub fn run(&mut self) -> Result<StochasticRoot> {
+        if !(self.lower < self.upper && self.lower.is_finite() && self.upper.is_finite())
+            || self.samples < 2
+            || self.max_iterations == 0
+            || !(self.confidence > 0. && self.confidence < 1.)
+        {
+            return Err(RootError::BadParams);
+        }
+        // The signs at both bounds and at each iteration are tested
+        let critical =
+            -inverse_normal_cdf(0.5 * (1. - self.confidence) / (self.max_iterations + 2) as f64);
+        let mut evaluations = 0;
+        let mut bound_signs = [0.; 2];
+        for (sign, &x) in bound_signs.iter_mut().zip(&[self.lower, self.upper]) {
+            let estimate = self.estimate(x, &mut evaluations)?;
+            if estimate.mean() == 0. && estimate.error() == 0. {
+                return Ok(StochasticRoot {
+                    root: x,
+                    lower: x,
+                    upper: x,
+                    evaluations,
+                    iterations: 0,
+                });
+            }
+            *sign = estimate.mean().signum();
+            if estimate.mean().abs() <= critical * estimate.error() {
+                return Err(RootError::NoBracket { iterations: 0 });
+            }
+        }
+        if bound_signs[0] == bound_signs[1] {
+            return Err(RootError::NoBracket { iterations: 0 });
+        }
+        let increasing = bound_signs[1] > 0.;
 
-        while (self.lower - self.upper).abs() > self.tol {
-            let middle = (self.lower + self.upper) * 0.5;
-            let middle_value = (self.f)(middle);
+        // Piecewise uniform distribution of the root
+        let mut points = vec![self.lower, self.upper];
+        let mut masses = vec![1.];
+        // Bracket of significant signs
+        let (mut lower, mut upper) = (self.lower, self.upper);
+        let mut iterations = 0;
+        while iterations < self.max_iterations && upper - lower > self.tol {
+            // The median locates the root, the midpoints with the bounds shrink the bracket
+            let median = quantile(&points, &masses, 0.5).max(lower).min(upper);
+            let x = match iterations % 3 {
+                0 => median,
+                1 => 0.5 * (lower + median),
+                _ => 0.5 * (median + upper),
+            };
+            let index = split(&mut points, &mut masses, x);
+            let estimate = self.estimate(x, &mut evaluations)?;
+            iterations += 1;
 
-            if middle_value * upper_value > 0. {
-                self.upper = middle;
-                upper_value = middle_value;
+            if estimate.mean() == 0. && estimate.error() == 0. {
+                return Ok(StochasticRoot {
+                    root: x,
+                    lower: x,
+                    upper: x,
+                    evaluations,
+                    iterations,
+                });
+            }
+            let z = estimate.mean() / estimate.error();
+            // Whether the root is more likely below x
+            let below = (estimate.mean() > 0.) == increasing;
+            // A significant sign rules out the other side
+            let probability = if z.abs() > critical {
+                if below {
+                    upper = upper.min(x);
+                } else {
+                    lower = lower.max(x);
+                }
+                1.
             } else {
-                self.lower = middle;
+                normal_cdf(z.abs()).min(MAX_SIGN_PROBABILITY)
+            };
+            for (i, mass) in masses.iter_mut().enumerate() {
+                *mass *= if (i < index) == below {
+                    probability
+                } else {
+                    1. - probability
+                };
+            }
+            let total: f64 = masses.iter().sum();
+            if total > 0. {
+                masses.iter_mut().for_each(|mass| *mass /= total);
+            }
+        }
+
+        Ok(StochasticRoot {
+            root: quantile(&points, &masses, 0.5).max(lower).min(upper),
+            lower,
+            upper,
+            evaluations,
+            iterations,
+        })
+    }
+
+    /// Mean and standard error of `samples` realizations of the function at `x`.
+    fn estimate(&mut self, x: f64, evaluations: &mut usize) -> Result<average::Variance> {
+        let mut estimate = average::Variance::new();
+        for _ in 0..self.samples {
+            let value = (self.f)(x);
+            *evaluations += 1;
+            if !value.is_finite() {
+                return Err(RootError::NotFinite { x, value });
             }
+            estimate.add(value);
         }
-        Ok((self.lower + self.upper) * 0.5)
+        Ok(estimate)
     }
 }
 
+/// Upper bound on the probability that the sign of an estimate is correct,
+/// so that a wrong decision can still be corrected by later ones.
+const MAX_SIGN_PROBABILITY: f64 = 1. - 1e-6;
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * statrs::function::erf::erfc(-x / std::f64::consts::SQRT_2)
+}
+
+fn inverse_normal_cdf(p: f64) -> f64 {
+    -std::f64::consts::SQRT_2 * statrs::function::erf::erfc_inv(2. * p)
+}
+
+/// Quantile `p` of the piecewise uniform distribution with the given masses between consecutive points.
+fn quantile(points: &[f64], masses: &[f64], p: f64) -> f64 {
+    let mut cumulative = 0.;
+    for (i, &mass) in masses.iter().enumerate() {
+        if cumulative + mass >= p && mass > 0. {
+            let fraction = ((p - cumulative) / mass).clamp(0., 1.);
+            return points[i] + fraction * (points[i + 1] - points[i]);
+        }
+        cumulative += mass;
+    }
+    points[points.len() - 1]
+}
+
+/// Adds `x` to the points, splitting the mass of the piece containing it proportionally to length.
+///
+/// Returns the index of the first piece above `x`.
+fn split(points: &mut Vec<f64>, masses: &mut Vec<f64>, x: f64) -> usize {
+    let index = points.iter().position(|&point| point >= x).unwrap();
+    if points[index] == x {
+        return index;
+    }
+    let (left, right) = (points[index - 1], points[index]);
+    let lower_mass = masses[index - 1] * (x - left) / (right - left);
+    masses[index - 1] -= lower_mass;
+    masses.insert(index - 1, lower_mass);
+    points.insert(index, x);
+    index
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::Rng;
     use test_case::test_case;
 
     #[test_case(|x| x, 0. ; "identity")]
@@ -209,4 +887,215 @@ mod tests {
         println!("Root finded: {}", root);
         assert!(root.abs() < 1e-6);
     }
+
+    /// Finds a root with the given method, returning it together with the number of evaluations.
+    fn find_root<F, M>(f: F, init: f64, method: M, tol: f64) -> (f64, usize)
+    where
+        F: Fn(f64) -> f64,
+        M: RootFinder,
+    {
+        let evaluations = std::cell::Cell::new(0);
+        let counted = |x: f64| {
+            evaluations.set(evaluations.get() + 1);
+            f(x)
+        };
+        let mut root_finding = ExpBinary::with_method(&counted, init, method);
+        root_finding.set_tol(tol);
+        let root = root_finding.run().unwrap();
+        (root, evaluations.get())
+    }
+
+    #[test_case(|x| x, 100. ; "identity from positive far away")]
+    #[test_case(|x| x * (x - 1000.), 200. ; "quadratic from positive far away")]
+    #[test_case(|x| { if x < 0. { -(-x).sqrt() } else { x.sqrt() } }, -100. ; "symmetric sqrt from negative far away")]
+    #[test_case(|x| { if x < 0. { -(-x).sqrt() - 1. } else { x.sqrt() + 1. } }, 100. ; "displaced symmetric sqrt from positive far away")]
+    #[test_case(|x| (x + 1e-3).exp() - (1e-3f64).exp(), 3. ; "exponential")]
+    fn methods<F>(f: F, init: f64)
+    where
+        F: Fn(f64) -> f64,
+    {
+        let (bisection, _) = find_root(&f, init, Bisection, 1e-15);
+        let (brent, _) = find_root(&f, init, Brent, 1e-15);
+        let (illinois, _) = find_root(&f, init, Illinois, 1e-15);
+        println!("Roots finded: {}, {}, {}", bisection, brent, illinois);
+        assert!(bisection.abs() < 1e-14);
+        assert!(brent.abs() < 1e-14);
+        assert!(illinois.abs() < 1e-14);
+    }
+
+    #[test_case(|x| x * (x - 1000.) + 10., 200. ; "quadratic")]
+    #[test_case(|x| x.exp() - 2., 0. ; "exponential")]
+    #[test_case(|x| (x - 0.3).powi(3) + 0.01 * (x - 0.3), 0. ; "cubic")]
+    fn methods_evaluations<F>(f: F, init: f64)
+    where
+        F: Fn(f64) -> f64,
+    {
+        let (bisection, bisection_evaluations) = find_root(&f, init, Bisection, 1e-15);
+        let (brent, brent_evaluations) = find_root(&f, init, Brent, 1e-15);
+        let (illinois, illinois_evaluations) = find_root(&f, init, Illinois, 1e-15);
+        println!(
+            "Evaluations: {}, {}, {}",
+            bisection_evaluations, brent_evaluations, illinois_evaluations
+        );
+        assert!((brent - bisection).abs() < 1e-14 * bisection.abs().max(1.));
+        assert!((illinois - bisection).abs() < 1e-14 * bisection.abs().max(1.));
+        // The exponential search is shared
+        let (_, bracketing_evaluations) = find_root(&f, init, Bisection, f64::INFINITY);
+        assert!(
+            brent_evaluations - bracketing_evaluations
+                < (bisection_evaluations - bracketing_evaluations) / 2
+        );
+        assert!(
+            illinois_evaluations - bracketing_evaluations
+                < (bisection_evaluations - bracketing_evaluations) / 2
+        );
+    }
+
+    /// Runs the search with the given method, recording the points where the function is evaluated.
+    fn report_with_points<F, M>(f: F, init: f64, method: M) -> (RootReport, Vec<f64>)
+    where
+        F: Fn(f64) -> f64,
+        M: RootFinder,
+    {
+        let points = std::cell::RefCell::new(Vec::new());
+        let recorded = |x: f64| {
+            points.borrow_mut().push(x);
+            f(x)
+        };
+        let mut root_finding = ExpBinary::with_method(&recorded, init, method);
+        root_finding.set_tol(1e-12);
+        let report = root_finding.run_report().unwrap();
+        (report, points.into_inner())
+    }
+
+    #[test_case(|x| x * (x - 1000.) + 10., 200. ; "quadratic")]
+    #[test_case(|x| x.exp() - 2., 0. ; "exponential")]
+    #[test_case(|x| x, 0. ; "identity")]
+    #[test_case(|x| { if x < 0. { -(-x).sqrt() - 1. } else { x.sqrt() + 1. } }, 100. ; "displaced symmetric sqrt")]
+    fn run_report<F>(f: F, init: f64)
+    where
+        F: Fn(f64) -> f64,
+    {
+        let reports = [
+            report_with_points(&f, init, Bisection),
+            report_with_points(&f, init, Brent),
+            report_with_points(&f, init, Illinois),
+        ];
+        for (report, points) in reports.iter() {
+            println!("{:?}", report);
+            assert_eq!(report.evaluations, points.len());
+            let mut sorted = points.clone();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            sorted.dedup();
+            assert_eq!(sorted.len(), points.len());
+
+            assert!(report.lower <= report.root && report.root <= report.upper);
+            assert!(report.upper - report.lower <= 1e-12);
+            assert_eq!(report.value, f(report.root));
+            assert!(!same_sign(f(report.lower), f(report.upper)));
+        }
+    }
+
+    #[test_case(|x: f64| (x - 5.).ln(), 0., -1e-6; "nan")]
+    #[test_case(|x: f64| 1. / (x - 1e-6), 0., 1e-6; "infinite")]
+    fn not_finite<F>(f: F, init: f64, point: f64)
+    where
+        F: Fn(f64) -> f64,
+    {
+        let mut root_finding = ExpBinary::new(&f, init);
+        match root_finding.run() {
+            Err(RootError::NotFinite { x, value }) => {
+                assert_eq!(x, point);
+                assert!(!value.is_finite());
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn no_bracket() {
+        let mut root_finding = ExpBinary::new(|x: f64| x * x + 1., 0.);
+        root_finding.set_max_exp_iterations(10);
+        assert_eq!(
+            root_finding.run(),
+            Err(RootError::NoBracket { iterations: 11 })
+        );
+    }
+
+    #[test]
+    fn vanishing_bound() {
+        let mut root_finding = ExpBinary::new(|x: f64| x - 1e-6, 0.);
+        let report = root_finding.run_report().unwrap();
+        assert_eq!(report.root, 1e-6);
+        assert_eq!(report.value, 0.);
+        assert_eq!(report.evaluations, 2);
+    }
+
+    #[test_case(0.3, 1., 1.; "noisy")]
+    #[test_case(-2., 0.1, 0.2; "slightly noisy")]
+    #[test_case(0.3, 0., 1e-12; "deterministic")]
+    fn probabilistic_bisection(root: f64, noise: f64, width: f64) {
+        let repetitions = 40;
+        let mut covered = 0;
+        for seed in 0..repetitions {
+            let mut rng = crate::tests::rng(seed);
+            let normal = rand_distr::Normal::new(0., noise.max(f64::MIN_POSITIVE)).unwrap();
+            let noisy = |x: f64| root - x + rng.sample(normal);
+            let mut root_finding = ProbabilisticBisection::new(noisy, -10., 10., 100);
+            root_finding.set_max_iterations(60);
+            let result = root_finding.run().unwrap();
+
+            assert!(result.lower <= result.root && result.root <= result.upper);
+            assert!(result.upper - result.lower < width);
+            assert_eq!(result.evaluations, 100 * (result.iterations + 2));
+            if result.lower <= root && root <= result.upper {
+                covered += 1;
+            }
+        }
+        assert!(covered >= 38);
+    }
+
+    #[test]
+    fn probabilistic_bisection_tolerance() {
+        let mut root_finding = ProbabilisticBisection::new(|x: f64| x - 1., 0., 3., 2);
+        root_finding.set_tol(1e-6);
+        let result = root_finding.run().unwrap();
+        assert!(result.upper - result.lower <= 1e-6);
+        assert!(result.iterations < 40);
+        assert!((result.root - 1.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn probabilistic_bisection_errors() {
+        let mut root_finding = ProbabilisticBisection::new(|x: f64| x * x + 1., -1., 1., 10);
+        assert_eq!(
+            root_finding.run(),
+            Err(RootError::NoBracket { iterations: 0 })
+        );
+        root_finding.set_samples(1);
+        assert_eq!(root_finding.run(), Err(RootError::BadParams));
+
+        let mut root_finding = ProbabilisticBisection::new(|x: f64| x.ln(), -1., 1., 10);
+        assert!(matches!(
+            root_finding.run(),
+            Err(RootError::NotFinite { .. })
+        ));
+
+        // The sign at the lower bound is lost in the noise
+        let mut rng = crate::tests::rng(3);
+        let noisy = |x: f64| x + rng.gen_range(-1., 1.);
+        let mut root_finding = ProbabilisticBisection::new(noisy, -1e-3, 1., 10);
+        assert_eq!(
+            root_finding.run(),
+            Err(RootError::NoBracket { iterations: 0 })
+        );
+    }
+
+    #[test]
+    fn probabilistic_bisection_root_at_bound() {
+        let mut root_finding = ProbabilisticBisection::new(|x: f64| x - 1., 0., 1., 10);
+        let result = root_finding.run().unwrap();
+        assert_eq!((result.lower, result.root, result.upper), (1., 1., 1.));
+        assert_eq!((result.evaluations, result.iterations), (20, 0));
+    }
 }
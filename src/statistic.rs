@@ -2,119 +2,103 @@
 
 // Crates
 use noisy_float::prelude::*;
-use quadrature::integrate;
 
 // Traits
 use rand::distributions::Distribution;
 use rand::Rng;
-use statrs::{
-    distribution::{Continuous, Univariate},
-    statistics::Mean,
-};
+use statrs::statistics::{Mean, Variance};
 
 // Structs
-use crate::distribution::Normal;
-use crate::Parameters;
+use crate::error::{Result, StatsError};
+use crate::fixation::Fixation;
+use crate::{Dominance, Selection};
+
+// Functions
+use statrs::function::{
+    factorial::{ln_binomial, ln_factorial},
+    gamma::ln_gamma,
+};
 
 // Types
 use noisy_float::types::R64; // finite f64.
 
 // Constants
-use crate::constants::{EPS, T, U};
+use crate::constants::{T, U};
 
-#[derive(Debug, Copy, Clone)]
+/// Substitutions between two lineages, for mutations with the given selection and dominance.
+///
+/// # Examples
+///
+/// ```
+/// use sandpiper::prelude::*;
+///
+/// let selection = Selection::Gamma { shape: 0.3, scale: 0.01 };
+/// let dominance = Dominance::KacserBurns { constant: 100. };
+/// let subs = Substitutions::new(N_SANDPIPER, selection, dominance).unwrap();
+/// ```
+#[derive(Debug, Clone)]
 pub struct Substitutions {
-    n: R64,
+    n: u64,
     u: R64,
     t: R64,
-    param: Parameters,
+    selection: Selection,
+    dominance: Dominance,
 }
 
 impl Substitutions {
-    pub fn new(n: u64, param: Parameters) -> Self {
-        let n: R64 = r64(n as f64);
-        let t: R64 = r64(T as f64);
-        let u: R64 = r64(U);
-
-        Substitutions { n, u, t, param }
+    /// Substitutions in a population of size `n`, with the mutation rate `U`
+    /// and the divergence time `T` of the sandpiper.
+    pub fn new(n: u64, selection: Selection, dominance: Dominance) -> Result<Self> {
+        Substitutions::with_divergence(n, U, T as f64, selection, dominance)
     }
 
-    fn h(&self, s: R64) -> R64 {
-        if s >= 0.0 {
-            r64(0.5)
-        } else {
-            r64(0.5) * (s * self.param.beta).exp()
-        }
-    }
-
-    /// Returns the number of mutations in one generation and in case of mutation
-    /// for the given positive selection coefficient.
+    /// Substitutions in a population of size `n`, with the given mutation rate per site and generation
+    /// and divergence time in generations.
     ///
-    fn positive_selection(&self, n: R64, s: R64) -> R64 {
-        let partial;
-        if s < 1e-6 {
-            let n_inv = r64(1.0) / n;
-            partial = n_inv * 0.5
-                + (-n_inv * 0.5 + 1.) * 0.5 * s
-                + (n * 2. + n_inv - 3.) * 0.08333333333333333 * s * s
-        } else {
-            partial = (-(-s).exp() + 1.) / (-(-n * 2. * s).exp() + 1.)
-        }
-        n * partial
-    }
-
-    /// Returns the number of mutations in one generation and in case of mutation
-    /// for the given negative selection coefficient.
+    /// # Examples
     ///
-    fn negative_selection(&self, n: R64, s: R64) -> f64 {
-        let h = self.h(s);
-
-        let numerator_integrand = |x: f64| -> f64 {
-            (-s * 2. * ((-h * 2. + 1.) / n * x * x + h * 2. * x - (-h * 2. + 1.) / (n * 4.) - h))
-                .exp()
-                .into()
-        };
-        let denominator_integrand = |x: f64| -> f64 {
-            (-n * 2. * s * ((-h * 2. + 1.) * x * x + h * 2. * x - 1.))
-                .exp()
-                .into()
-        };
-        let scale: f64 = (-s * 2. * ((-h * 2. + 1.) / 4. * n + h - n)).exp().into();
-
-        let numerator = integrate(numerator_integrand, 0.0, 0.5, EPS).integral;
-        let denominator = integrate(denominator_integrand, 0.0, 1.0, EPS).integral;
+    /// ```
+    /// use sandpiper::prelude::*;
+    ///
+    /// let selection = Selection::Fixed(-1e-6);
+    /// let dominance = Dominance::Fixed(0.5);
+    /// let subs = Substitutions::with_divergence(10_000, 1e-8, 1e6, selection, dominance).unwrap();
+    /// ```
+    pub fn with_divergence(
+        n: u64,
+        mutation_rate: f64,
+        divergence_time: f64,
+        selection: Selection,
+        dominance: Dominance,
+    ) -> Result<Self> {
+        if n == 0
+            || !(mutation_rate >= 0. && mutation_rate.is_finite())
+            || !(divergence_time >= 0. && divergence_time.is_finite())
+        {
+            return Err(StatsError::BadParams);
+        }
+        selection.check()?;
+        dominance.check()?;
+        let t: R64 = r64(divergence_time);
+        let u: R64 = r64(mutation_rate);
 
-        numerator / denominator * scale
+        Ok(Substitutions {
+            n,
+            u,
+            t,
+            selection,
+            dominance,
+        })
     }
 }
 
 impl Distribution<R64> for Substitutions {
+    /// Returns the expected fraction of sites with a substitution
+    /// for a single sampled selection coefficient.
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> R64 {
-        let s = r64(self.param.skew_normal.sample(rng));
-        let h = self.h(s);
-
-        let aux = h * -2. + 1.;
-
-        if aux == 0. {
-            ((-s).exp() - 1.) / ((self.n * s * -2.).exp() - 1.)
-        } else {
-            let normal = Normal::new(0., 1.).unwrap();
+        let s = self.selection.sample(rng);
 
-            let current_mean = -h / aux;
-
-            println!("{:?}", self.n);
-            println!("{:?}", s);
-            println!("{:?}", (h * -2. + 1.));
-            println!("{:?}", self.n * s * (h * -2. + 1.));
-            let current_inv_std = (self.n * s * (h * -2. + 1.)).sqrt() * 2.;
-
-            let normalize = |x: R64| -> R64 { (x - current_mean) * current_inv_std };
-            let value = normal.cdf(normalize(r64(1.) / (self.n * 2.)).into())
-                - normal.cdf(normalize(r64(0.)).into());
-
-            self.n * self.u * self.t * value * 2.
-                / (normal.cdf(normalize(r64(1.)).into()) - normal.cdf(normalize(r64(0.)).into()))
-        }
+        r64(self.conditional_substitutions(s, &|_, substitutions| substitutions))
     }
 }
 
@@ -131,27 +115,217 @@ impl Mean<R64> for Substitutions {
     /// let alpha = -1.0;
     /// let beta = 1000.0;
     /// let parameters = Parameters::new(mu, sigma, alpha, beta).unwrap();
-    /// let subs = Substitutions::new(N_SANDPIPER, parameters);
+    /// let subs = Substitutions::new(N_SANDPIPER, parameters.selection(), parameters.dominance()).unwrap();
     ///
     /// println!("Expected value of substitutions: {:?}", subs.mean());
     /// ```
     fn mean(&self) -> R64 {
-        let integrand = |s: f64| -> f64 {
-            if s > 0.0 {
-                (self.positive_selection(self.n, r64(s)) * self.param.skew_normal.pdf(s)).into()
+        r64(self.expected_substitutions().integral)
+    }
+}
+
+impl Substitutions {
+    /// Returns the expected fraction of sites with a substitution,
+    /// together with an estimate of the numerical error.
+    ///
+    /// # Remarks
+    ///
+    /// The selection is integrated over the range between its quantiles of probability `1e-12`
+    /// and `1 - 1e-12`, intersected with its bounds if they are set,
+    /// so that narrow distributions of selection are resolved wherever they lie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sandpiper::prelude::*;
+    ///
+    /// let selection = Selection::SkewNormal { location: -1e-4, scale: 1e-5, shape: -1., bounds: None };
+    /// let subs = Substitutions::new(N_SANDPIPER, selection, Dominance::Fixed(0.5)).unwrap();
+    /// let output = subs.expected_substitutions();
+    /// assert!(output.error_estimate < 1e-6 * output.integral);
+    /// ```
+    pub fn expected_substitutions(&self) -> quadrature::Output {
+        self.expectation_given(&self.selection, &|_, substitutions| substitutions)
+    }
+
+    /// Returns the expected fraction of sites with a substitution from a beneficial mutation,
+    /// together with an estimate of the numerical error.
+    pub fn expected_adaptive_substitutions(&self) -> quadrature::Output {
+        self.expectation_given(&self.selection, &|s, substitutions| {
+            if s > 0. {
+                substitutions
             } else {
-                (self.negative_selection(self.n, r64(s)) * self.param.skew_normal.pdf(s)).into()
+                0.
+            }
+        })
+    }
+
+    /// Returns `g(s, m)`, where `m` is the expected substitutions per site for the selection coefficient `s`,
+    /// averaged over the dominance if it is random.
+    ///
+    /// The expected substitutions per site are `2 U T` times the number of mutations in one generation
+    /// and in case of mutation, `N` times the fixation probability.
+    fn conditional_substitutions<G>(&self, s: f64, g: &G) -> f64
+    where
+        G: Fn(f64, f64) -> f64,
+    {
+        self.dominance.expectation(s, |h| {
+            let fixation = Fixation::new(self.n, s, h).unwrap();
+            let ln_substitutions = (self.u * self.t * 2.).raw().ln()
+                + (self.n as f64).ln()
+                + fixation.ln_probability();
+            g(s, ln_substitutions.exp())
+        })
+    }
+
+    /// Returns the average of `conditional_substitutions` when the selection follows `selection`.
+    fn expectation_given<G>(&self, selection: &Selection, g: &G) -> quadrature::Output
+    where
+        G: Fn(f64, f64) -> f64,
+    {
+        selection.average(self.n, &|s| self.conditional_substitutions(s, g))
+    }
+}
+
+/// Model for the substitutions at each site.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SiteModel {
+    /// The number of substitutions at each site is Poisson, and all of them are counted.
+    Poisson,
+    /// Each site is counted once if it has at least one substitution.
+    Binomial,
+}
+
+/// Observed number of substitutions across `sites` independent sites,
+/// each with its own selection coefficient drawn from the distribution of fitness effects.
+///
+/// Under `SiteModel::Binomial`, each site differs with probability `1 - exp(-m(s))`,
+/// where `m(s)` is the expected number of substitutions of a site with selection `s`,
+/// so that the count is binomial with the probability averaged over the selection.
+/// Under `SiteModel::Poisson`, the count is a sum of Poisson variables mixed over the selection,
+/// whose likelihood is approximated by the negative binomial with the same mean and variance.
+///
+/// # Examples
+///
+/// ```
+/// use sandpiper::prelude::*;
+///
+/// let selection = Selection::Fixed(-1e-5);
+/// let subs = Substitutions::new(N_SANDPIPER, selection, Dominance::Fixed(0.5)).unwrap();
+/// let counts = SubstitutionCounts::new(subs, 1_000_000, SiteModel::Binomial).unwrap();
+/// println!("Log-likelihood of 600 substitutions: {}", counts.ln_likelihood(600));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SubstitutionCounts {
+    sites: u64,
+    model: SiteModel,
+    site_mean: f64,
+    site_variance: f64,
+}
+
+impl SubstitutionCounts {
+    /// Computes the distribution of the count of substitutions over `sites` sites.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `sites` is zero.
+    pub fn new(substitutions: Substitutions, sites: u64, model: SiteModel) -> Result<Self> {
+        if sites == 0 {
+            return Err(StatsError::BadParams);
+        }
+        let selection = &substitutions.selection;
+        let (site_mean, site_variance) = match model {
+            SiteModel::Poisson => {
+                let first = substitutions.expected_substitutions().integral;
+                let second = substitutions
+                    .expectation_given(selection, &|_, substitutions| substitutions * substitutions)
+                    .integral;
+                (first, first + (second - first * first).max(0.))
+            }
+            SiteModel::Binomial => {
+                let probability = substitutions
+                    .expectation_given(selection, &|_, substitutions| -(-substitutions).exp_m1())
+                    .integral;
+                (probability, probability * (1. - probability))
             }
         };
-        let expectation = integrate(integrand, -10.0, 10.0, EPS).integral;
+        Ok(SubstitutionCounts {
+            sites,
+            model,
+            site_mean,
+            site_variance,
+        })
+    }
+
+    /// Returns the logarithm of the probability of observing `count` substitutions.
+    pub fn ln_likelihood(&self, count: u64) -> f64 {
+        let k = count as f64;
+        match self.model {
+            SiteModel::Binomial => {
+                if count > self.sites {
+                    return f64::NEG_INFINITY;
+                }
+                let p = self.site_mean;
+                // Zero powers are one even when the base is zero
+                let power = |exponent: f64, ln_base: f64| {
+                    if exponent == 0. {
+                        0.
+                    } else {
+                        exponent * ln_base
+                    }
+                };
+                ln_binomial(self.sites, count)
+                    + power(k, p.ln())
+                    + power((self.sites - count) as f64, (-p).ln_1p())
+            }
+            SiteModel::Poisson => {
+                let (mean, variance) = (self.mean(), self.variance());
+                if mean == 0. {
+                    return if count == 0 { 0. } else { f64::NEG_INFINITY };
+                }
+                let inverse_size = (variance - mean) / (mean * mean);
+                if inverse_size < POISSON_DISPERSION {
+                    return k * mean.ln() - mean - ln_factorial(count);
+                }
+                let size = inverse_size.recip();
+                ln_gamma(k + size)
+                    - ln_gamma(size)
+                    - ln_factorial(count)
+                    - size * (mean * inverse_size).ln_1p()
+                    + k * (mean / (size + mean)).ln()
+            }
+        }
+    }
+}
+
+impl Mean<f64> for SubstitutionCounts {
+    /// Returns the expected number of substitutions.
+    fn mean(&self) -> f64 {
+        self.sites as f64 * self.site_mean
+    }
+}
 
-        self.u * self.t * expectation * 2.
+impl Variance<f64> for SubstitutionCounts {
+    /// Returns the variance of the number of substitutions,
+    /// including the variation of selection between sites.
+    fn variance(&self) -> f64 {
+        self.sites as f64 * self.site_variance
+    }
+
+    fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
     }
 }
 
+/// Overdispersion `(variance - mean) / mean^2` below which the count is taken as Poisson,
+/// where the negative binomial would lose precision.
+const POISSON_DISPERSION: f64 = 1e-10;
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Parameters;
+    use statrs::distribution::{Continuous, Discrete};
     use test_case::test_case;
 
     #[test_case(0., 100., 0.5; "zero")]
@@ -164,11 +338,260 @@ mod tests {
             for sigma in [0.1, 1., 2.].iter() {
                 for alpha in [-1., 0., 1.].iter() {
                     let parameters = Parameters::new(*mu, *sigma, *alpha, beta).unwrap();
-                    let subs = Substitutions::new(crate::N_SANDPIPER, parameters);
+                    let subs = Substitutions::new(
+                        crate::N_SANDPIPER,
+                        parameters.selection(),
+                        parameters.dominance(),
+                    )
+                    .unwrap();
 
-                    assert_eq!(subs.h(r64(s)), expected);
+                    assert_eq!(subs.dominance.coefficient(s), Some(expected));
                 }
             }
         }
     }
+
+    #[test]
+    fn mean_mixture_of_equal_components() {
+        let selection = Selection::Gamma {
+            shape: 0.5,
+            scale: 1e-4,
+        };
+        let dominance = Dominance::Fixed(0.3);
+        let subs =
+            Substitutions::new(crate::N_SANDPIPER, selection.clone(), dominance.clone()).unwrap();
+        let mixture = Substitutions::new(
+            crate::N_SANDPIPER,
+            Selection::Mixture(vec![(1., selection.clone()), (3., selection)]),
+            dominance,
+        )
+        .unwrap();
+
+        let expected: f64 = subs.mean().into();
+        let result: f64 = mixture.mean().into();
+        assert!(expected > 0.);
+        assert!((result - expected).abs() < 1e-12 * expected);
+    }
+
+    #[test]
+    fn sample_fixed_selection() {
+        let subs = Substitutions::new(
+            crate::N_SANDPIPER,
+            Selection::Fixed(-1e-6),
+            Dominance::Exponential { rate: 100. },
+        )
+        .unwrap();
+        let mut rng = crate::tests::rng(1);
+
+        assert_eq!(subs.sample(&mut rng), subs.mean());
+    }
+
+    #[test_case(1.2e-8, 1e5, 2.4e-8, 1e5; "mutation rate")]
+    #[test_case(1.2e-8, 1e5, 1.2e-8, 3e5; "divergence time")]
+    fn with_divergence_proportional(
+        mutation_rate: f64,
+        divergence_time: f64,
+        other_mutation_rate: f64,
+        other_divergence_time: f64,
+    ) {
+        let selection = Selection::SkewNormal {
+            location: -1e-5,
+            scale: 1e-5,
+            shape: -1.,
+            bounds: None,
+        };
+        let dominance = Dominance::Fixed(0.5);
+        let subs = Substitutions::with_divergence(
+            20_000,
+            mutation_rate,
+            divergence_time,
+            selection.clone(),
+            dominance.clone(),
+        )
+        .unwrap();
+        let other = Substitutions::with_divergence(
+            20_000,
+            other_mutation_rate,
+            other_divergence_time,
+            selection,
+            dominance,
+        )
+        .unwrap();
+
+        let expected =
+            other_mutation_rate * other_divergence_time / (mutation_rate * divergence_time);
+        let result: f64 = (other.mean() / subs.mean()).into();
+        assert!((result - expected).abs() < 1e-12 * expected);
+    }
+
+    #[test_case(crate::N_SANDPIPER, -0.01, 1.; "sandpiper")]
+    #[test_case(crate::N_REDNECK, -0.01, 1.; "redneck")]
+    #[test_case(crate::N_REDNECK, -1., 0.1; "redneck lethal")]
+    fn mean_extreme_corners(population: u64, mu: f64, sigma: f64) {
+        // With strong selection, new beneficial mutations fix with probability `1 - exp(-2 h s)`
+        // (see the Laplace approximation in the tests of `Fixation`), with `h = 0.5` for them,
+        // while the contribution of the others is of relative order `1 / N`
+        let random_selection = crate::SkewNormal::new(mu, sigma, -1.).unwrap();
+        let scale = 2. * U * T as f64 * population as f64;
+        let expected = scale
+            * quadrature::integrate(
+                |s| random_selection.pdf(s) * -(-s).exp_m1(),
+                0.,
+                mu + 40. * sigma,
+                1e-14,
+            )
+            .integral;
+        for &beta in &[0., 1000.] {
+            let parameters = Parameters::new(mu, sigma, -1., beta).unwrap();
+            let subs =
+                Substitutions::new(population, parameters.selection(), parameters.dominance())
+                    .unwrap();
+
+            let result: f64 = subs.mean().into();
+            assert!(result.is_finite() && result >= 0.);
+            // The tails of probability `SELECTION_TAIL` are left out of the integration
+            assert!(
+                (result - expected).abs()
+                    < 1e-6 * expected + 2. * crate::distribution::SELECTION_TAIL * scale
+            );
+        }
+    }
+
+    #[test]
+    fn mean_narrow_corner() {
+        let (population, mu, sigma) = (crate::N_REDNECK, -1e-5, 1e-5);
+        let random_selection = crate::SkewNormal::new(mu, sigma, -1.).unwrap();
+        let scale = 2. * U * T as f64 * population as f64;
+        for &beta in &[0., 1000.] {
+            let parameters = Parameters::new(mu, sigma, -1., beta).unwrap();
+            let dominance = parameters.dominance();
+            let subs =
+                Substitutions::new(population, parameters.selection(), dominance.clone()).unwrap();
+            // Selection is not strong here, so the fixation probability is integrated directly,
+            // over pieces of the width of the selection for the quadrature to resolve them
+            let conditional = |s: f64| {
+                let h = dominance.coefficient(s).unwrap();
+                random_selection.pdf(s) * Fixation::new(population, s, h).unwrap().probability()
+            };
+            let expected = scale
+                * (-40..40)
+                    .map(|k| {
+                        let lower = mu + k as f64 * sigma;
+                        quadrature::integrate(conditional, lower, lower + sigma, 1e-14).integral
+                    })
+                    .sum::<f64>();
+
+            let result: f64 = subs.mean().into();
+            assert!(result.is_finite() && result >= 0.);
+            assert!((result - expected).abs() < 1e-8 * expected);
+        }
+    }
+
+    #[test_case(crate::N_SANDPIPER, -1e-4, None; "sandpiper")]
+    #[test_case(crate::N_REDNECK, -1e-5, None; "redneck")]
+    #[test_case(crate::N_REDNECK, 1e-5, Some((0., 1.)); "redneck bounded")]
+    fn expected_substitutions_narrow_selection(
+        population: u64,
+        location: f64,
+        bounds: Option<(f64, f64)>,
+    ) {
+        let dominance = Dominance::Fixed(0.3);
+        let selection = Selection::SkewNormal {
+            location,
+            scale: 1e-9,
+            shape: -1.,
+            bounds,
+        };
+        let subs = Substitutions::new(population, selection, dominance.clone()).unwrap();
+        let fixed = Substitutions::new(population, Selection::Fixed(location), dominance).unwrap();
+
+        let output = subs.expected_substitutions();
+        let expected = fixed.expected_substitutions().integral;
+        assert!((output.integral - expected).abs() < 1e-3 * expected);
+        assert!(output.error_estimate < 1e-6 * output.integral);
+    }
+
+    #[test_case(SiteModel::Poisson; "poisson")]
+    #[test_case(SiteModel::Binomial; "binomial")]
+    fn counts_fixed_selection(model: SiteModel) {
+        let subs = Substitutions::new(
+            crate::N_SANDPIPER,
+            Selection::Fixed(-1e-5),
+            Dominance::Fixed(0.5),
+        )
+        .unwrap();
+        let site_mean: f64 = subs.mean().into();
+        let sites = 1_000_000;
+        let counts = SubstitutionCounts::new(subs, sites, model).unwrap();
+
+        let expected = match model {
+            SiteModel::Poisson => sites as f64 * site_mean,
+            SiteModel::Binomial => -(sites as f64) * (-site_mean).exp_m1(),
+        };
+        assert!((counts.mean() - expected).abs() < 1e-10 * expected);
+        // Without variation of selection, the count is Poisson or binomial
+        let ln_likelihood = match model {
+            SiteModel::Poisson => statrs::distribution::Poisson::new(expected)
+                .unwrap()
+                .ln_pmf(600),
+            SiteModel::Binomial => {
+                statrs::distribution::Binomial::new(expected / sites as f64, sites)
+                    .unwrap()
+                    .ln_pmf(600)
+            }
+        };
+        assert!((counts.ln_likelihood(600) - ln_likelihood).abs() < 1e-8);
+    }
+
+    #[test_case(0., 0.; "never")]
+    #[test_case(1., 1.; "always")]
+    fn counts_binomial_degenerate(mutation_rate: f64, probability: f64) {
+        // Neutral substitutions accumulate at the mutation rate
+        let subs = Substitutions::with_divergence(
+            crate::N_SANDPIPER,
+            mutation_rate,
+            1e6,
+            Selection::Fixed(0.),
+            Dominance::Fixed(0.5),
+        )
+        .unwrap();
+        let sites = 1000;
+        let counts = SubstitutionCounts::new(subs, sites, SiteModel::Binomial).unwrap();
+        let certain = (probability * sites as f64) as u64;
+
+        assert_eq!(counts.mean(), certain as f64);
+        assert_eq!(counts.ln_likelihood(certain), 0.);
+        for &count in &[0, 1, sites - 1, sites] {
+            if count != certain {
+                assert_eq!(counts.ln_likelihood(count), f64::NEG_INFINITY);
+            }
+        }
+    }
+
+    #[test_case(Selection::Fixed(f64::NEG_INFINITY), Dominance::Fixed(0.5); "lethal")]
+    #[test_case(Selection::Fixed(f64::INFINITY), Dominance::Fixed(0.5); "infinitely beneficial")]
+    #[test_case(Selection::Fixed(0.), Dominance::Sigmoid { rate: f64::INFINITY }; "infinite sigmoid rate")]
+    fn new_invalid(selection: Selection, dominance: Dominance) {
+        assert!(matches!(
+            Substitutions::with_divergence(crate::N_SANDPIPER, U, T as f64, selection, dominance),
+            Err(StatsError::BadParams)
+        ));
+    }
+
+    #[test]
+    fn counts_poisson_overdispersed() {
+        let selection = Selection::Mixture(vec![
+            (1., Selection::Fixed(-1e-3)),
+            (1., Selection::Fixed(1e-4)),
+        ]);
+        let subs =
+            Substitutions::new(crate::N_SANDPIPER, selection, Dominance::Fixed(0.5)).unwrap();
+        let counts = SubstitutionCounts::new(subs, 100_000, SiteModel::Poisson).unwrap();
+
+        assert!(counts.variance() > counts.mean());
+        let total: f64 = (0..2 * counts.mean() as u64 + 1000)
+            .map(|k| counts.ln_likelihood(k).exp())
+            .sum();
+        assert!((total - 1.).abs() < 1e-8);
+    }
 }